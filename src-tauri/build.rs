@@ -1,3 +1,5 @@
 fn main() {
+    // Only the desktop app needs the Tauri config and icons
+    #[cfg(feature = "gui")]
     tauri_build::build()
 }
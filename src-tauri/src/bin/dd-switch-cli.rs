@@ -0,0 +1,5 @@
+// Command-line front end for headless machines; see `dd-switch-cli help`
+fn main() {
+    let code = dd_switch_lib::cli::run(std::env::args().skip(1).collect());
+    std::process::exit(code);
+}
@@ -0,0 +1,7 @@
+// Terminal profile switcher for SSH sessions; build with --features tui
+fn main() {
+    if let Err(e) = dd_switch_lib::tui::run() {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
+}
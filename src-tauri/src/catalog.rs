@@ -0,0 +1,120 @@
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use crate::{
+    app_dir, get_current_config, inherit, model_list, profile_id, profile_paths,
+    reapply_if_changed, write_atomic,
+};
+
+// Named model definitions that profiles list by reference with
+// {"ref": "<name>", ...overrides}, so one edit updates every profile using them
+pub(crate) type Catalog = BTreeMap<String, Value>;
+
+pub(crate) fn catalog_path() -> PathBuf {
+    app_dir().join("catalog.json")
+}
+
+pub(crate) fn load_catalog() -> Catalog {
+    fs::read_to_string(catalog_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_catalog(catalog: &Catalog) -> Result<(), String> {
+    let path = catalog_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(catalog).map_err(|e| e.to_string())?;
+    write_atomic(&path, &content)
+}
+
+pub(crate) fn model_ref(model: &Value) -> Option<&str> {
+    model.get("ref").and_then(|v| v.as_str())
+}
+
+// Replace a reference with the catalog entry, overlaid with the fields set
+// next to "ref". Models without "ref" are returned unchanged.
+pub(crate) fn expand_ref(model: &Value, catalog: &Catalog) -> Result<Value, String> {
+    let name = match model_ref(model) {
+        Some(name) => name,
+        None => return Ok(model.clone()),
+    };
+    let entry = catalog
+        .get(name)
+        .ok_or_else(|| format!("Unknown catalog model: {}", name))?;
+
+    let mut expanded = inherit::canonical_model(entry);
+    if let (Some(target), Value::Object(overrides)) =
+        (expanded.as_object_mut(), inherit::canonical_model(model))
+    {
+        for (key, value) in overrides {
+            if key != "ref" {
+                target.insert(key, value);
+            }
+        }
+    }
+    Ok(expanded)
+}
+
+// IDs of profiles that list the given catalog model
+fn catalog_users(name: &str) -> Vec<String> {
+    profile_paths()
+        .into_iter()
+        .filter(|path| {
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .as_ref()
+                .and_then(model_list)
+                .map(|models| models.iter().any(|m| model_ref(m) == Some(name)))
+                .unwrap_or(false)
+        })
+        .map(|path| profile_id(&path))
+        .collect()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn list_catalog() -> Catalog {
+    load_catalog()
+}
+
+// Add or replace a catalog model. If that changes what the active profile
+// resolves to, it is re-applied so settings.json picks up the change.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn save_catalog_model(name: String, model: Value) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Catalog model name cannot be empty".to_string());
+    }
+    if !model.is_object() {
+        return Err("Catalog model must be a JSON object".to_string());
+    }
+    if model_ref(&model).is_some() {
+        return Err("Catalog models cannot reference other catalog models".to_string());
+    }
+
+    let active = get_current_config();
+    let mut catalog = load_catalog();
+    catalog.insert(name, model);
+    save_catalog(&catalog)?;
+    reapply_if_changed(active)?;
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn delete_catalog_model(name: String) -> Result<(), String> {
+    let users = catalog_users(&name);
+    if !users.is_empty() {
+        return Err(format!("Catalog model is used by: {}", users.join(", ")));
+    }
+    let mut catalog = load_catalog();
+    if catalog.remove(&name).is_none() {
+        return Err("Catalog model not found".to_string());
+    }
+    save_catalog(&catalog)
+}
@@ -0,0 +1,287 @@
+// Headless front end for machines without a display (e.g. over SSH). It calls the
+// same functions as the Tauri commands and uses the same settings file.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+
+use crate::{
+    apply_config, apply_diff, convert_models, existing_profile, get_current_config, history,
+    import_current, lint, list_configs, mask_key, read_profile, rename_config, validate,
+    ConfigFile,
+};
+
+const USAGE: &str = "\
+Usage: dd-switch-cli [--json] <command> [args]
+
+Commands:
+  list                  List profiles; the active one is marked with *
+  current               Print the active profile
+  apply <profile>       Apply a profile to droid's settings.json
+  show <profile>        Show the models a profile applies, with keys masked
+  import [name]         Save the models in settings.json as a new profile
+  diff <profile> [other]
+                        What applying a profile would change, or how two profiles differ
+  validate [profile...] Check profiles (all of them by default)
+
+A profile is given by its ID (e.g. work/eu) or its display name.
+With --json, output is JSON instead of text.";
+
+struct Output {
+    json: bool,
+}
+
+impl Output {
+    fn print<T: Serialize>(&self, value: &T, text: impl FnOnce() -> String) {
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(value).unwrap_or_default());
+        } else {
+            let text = text();
+            if !text.is_empty() {
+                println!("{}", text);
+            }
+        }
+    }
+}
+
+// Look a profile up by ID or display name; names must be unambiguous
+fn find_profile(query: &str) -> Result<ConfigFile, String> {
+    let mut configs = list_configs();
+    if let Some(i) = configs.iter().position(|c| c.id == query) {
+        return Ok(configs.swap_remove(i));
+    }
+    let matches: Vec<ConfigFile> = configs.into_iter().filter(|c| c.name == query).collect();
+    match matches.len() {
+        0 => Err(format!("Profile not found: {}", query)),
+        1 => Ok(matches.into_iter().next().unwrap()),
+        _ => Err(format!(
+            "More than one profile is named {}: {}",
+            query,
+            matches.iter().map(|c| c.id.as_str()).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+fn list(out: &Output) -> Result<(), String> {
+    let active = get_current_config();
+    let configs = list_configs();
+    let width = configs.iter().map(|c| c.id.chars().count()).max().unwrap_or(0);
+    let rows: Vec<Value> = configs
+        .iter()
+        .map(|c| json!({"id": c.id, "name": c.name, "active": Some(&c.id) == active.as_ref()}))
+        .collect();
+    out.print(&rows, || {
+        configs
+            .iter()
+            .map(|c| {
+                let marker = if Some(&c.id) == active.as_ref() { "*" } else { " " };
+                format!("{} {:width$}  {}", marker, c.id, c.name, width = width)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+    Ok(())
+}
+
+fn current(out: &Output) -> Result<(), String> {
+    let active = get_current_config()
+        .and_then(|id| list_configs().into_iter().find(|c| c.id == id));
+    let value = active.as_ref().map(|c| json!({"id": c.id, "name": c.name}));
+    out.print(&value, || match &active {
+        Some(c) => format!("{}  {}", c.id, c.name),
+        None => String::new(),
+    });
+    match active {
+        Some(_) => Ok(()),
+        None => Err("No profile matches settings.json".to_string()),
+    }
+}
+
+fn apply(out: &Output, query: &str) -> Result<(), String> {
+    let config = find_profile(query)?;
+    apply_config(config.id.clone())?;
+    out.print(&json!({"id": config.id, "name": config.name}), || {
+        format!("Applied {}", config.name)
+    });
+    Ok(())
+}
+
+// The models applying a profile writes, with API keys masked
+pub(crate) fn masked_models(id: &str) -> Result<Vec<Value>, String> {
+    let models = convert_models(&read_profile(&existing_profile(id)?)?)?;
+    let mut models = models.as_array().cloned().unwrap_or_default();
+    for model in models.iter_mut() {
+        if let Some(key) = model.get("apiKey").and_then(|v| v.as_str()) {
+            model["apiKey"] = json!(mask_key(key));
+        }
+    }
+    Ok(models)
+}
+
+fn show(out: &Output, query: &str) -> Result<(), String> {
+    let config = find_profile(query)?;
+    let models = masked_models(&config.id)?;
+    out.print(&models, || {
+        let field = |m: &Value, key: &str| {
+            m.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string()
+        };
+        let mut lines = vec![format!("{} ({})", config.name, config.id)];
+        for m in &models {
+            lines.push(format!(
+                "  {}  {}  {}  {}  {}",
+                field(m, "displayName"),
+                field(m, "model"),
+                field(m, "provider"),
+                field(m, "baseUrl"),
+                field(m, "apiKey")
+            ));
+        }
+        lines.join("\n")
+    });
+    Ok(())
+}
+
+fn import(out: &Output, name: Option<&str>) -> Result<(), String> {
+    let id = import_current()?;
+    if let Some(name) = name {
+        rename_config(id.clone(), name.to_string())?;
+    }
+    out.print(&json!({"id": id}), || format!("Imported as {}", id));
+    Ok(())
+}
+
+// One line per model ("+", "-" or "~" and its name), then one per changed field
+pub(crate) fn diff_text(diffs: &[history::ModelDiff]) -> String {
+    if diffs.is_empty() {
+        return "No changes".to_string();
+    }
+    let mut lines = Vec::new();
+    for diff in diffs {
+        let sign = match diff.status.as_str() {
+            "added" => "+",
+            "removed" => "-",
+            _ => "~",
+        };
+        lines.push(format!("{} {}", sign, diff.display_name));
+        for change in &diff.changes {
+            let shown = |v: &Option<Value>| v.as_ref().map(|v| v.to_string()).unwrap_or_default();
+            lines.push(match diff.status.as_str() {
+                "changed" => format!(
+                    "    {}: {} -> {}",
+                    change.field,
+                    shown(&change.old),
+                    shown(&change.new)
+                ),
+                "added" => format!("    {}: {}", change.field, shown(&change.new)),
+                _ => format!("    {}: {}", change.field, shown(&change.old)),
+            });
+        }
+    }
+    lines.join("\n")
+}
+
+fn diff(out: &Output, query: &str, other: Option<&str>) -> Result<(), String> {
+    let config = find_profile(query)?;
+    let diffs = match other {
+        None => apply_diff(&config.id)?,
+        Some(other) => {
+            let other = find_profile(other)?;
+            let models = |id: &str| -> Result<Vec<Value>, String> {
+                let models = convert_models(&read_profile(&existing_profile(id)?)?)?;
+                Ok(models.as_array().cloned().unwrap_or_default())
+            };
+            history::diff_models(&models(&config.id)?, &models(&other.id)?)
+        }
+    };
+    out.print(&diffs, || diff_text(&diffs));
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ValidateReport {
+    id: String,
+    errors: Vec<validate::ValidationError>,
+    diagnostics: Vec<lint::Diagnostic>,
+}
+
+fn validate(out: &Output, queries: &[String]) -> Result<(), String> {
+    let configs = if queries.is_empty() {
+        list_configs()
+    } else {
+        queries.iter().map(|q| find_profile(q)).collect::<Result<Vec<_>, _>>()?
+    };
+    let mut reports = Vec::new();
+    for config in configs {
+        let content = fs::read_to_string(&config.path).map_err(|e| e.to_string())?;
+        let errors = validate::validate_profile_text(&content);
+        let diagnostics = match serde_json::from_str::<Value>(&content) {
+            Ok(value) if errors.is_empty() => lint::lint_value(&value),
+            _ => Vec::new(),
+        };
+        reports.push(ValidateReport { id: config.id, errors, diagnostics });
+    }
+
+    let failed = reports
+        .iter()
+        .filter(|r| !r.errors.is_empty() || r.diagnostics.iter().any(|d| d.severity == "error"))
+        .count();
+    out.print(&reports, || {
+        let mut lines = Vec::new();
+        for report in &reports {
+            for e in &report.errors {
+                lines.push(format!(
+                    "{}:{}:{}: error: {} ({})",
+                    report.id, e.line, e.column, e.message, e.pointer
+                ));
+            }
+            for d in &report.diagnostics {
+                lines.push(format!(
+                    "{}: {}: {} [{}] ({})",
+                    report.id, d.severity, d.message, d.rule, d.pointer
+                ));
+            }
+        }
+        lines.push(format!("{} profile(s) checked, {} with errors", reports.len(), failed));
+        lines.join("\n")
+    });
+    if failed > 0 {
+        return Err(format!("{} profile(s) have errors", failed));
+    }
+    Ok(())
+}
+
+// Run one command; returns the process exit code
+pub fn run(args: Vec<String>) -> i32 {
+    let json = args.iter().any(|a| a == "--json");
+    let args: Vec<String> = args.into_iter().filter(|a| a != "--json").collect();
+    let out = Output { json };
+
+    let command = args.first().map(|s| s.as_str()).unwrap_or("help");
+    let arg = args.get(1).map(|s| s.as_str());
+    let result = match (command, arg) {
+        ("list", None) => list(&out),
+        ("current", None) => current(&out),
+        ("apply", Some(query)) if args.len() == 2 => apply(&out, query),
+        ("show", Some(query)) if args.len() == 2 => show(&out, query),
+        ("import", _) if args.len() <= 2 => import(&out, arg),
+        ("diff", Some(query)) if args.len() <= 3 => {
+            diff(&out, query, args.get(2).map(|s| s.as_str()))
+        }
+        ("validate", _) => validate(&out, &args[1..]),
+        ("help" | "-h" | "--help", _) => {
+            println!("{}", USAGE);
+            return 0;
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return 2;
+        }
+    };
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
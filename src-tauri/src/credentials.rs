@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use crate::{
+    app_dir, get_current_config, model_list, profile_id, profile_paths, reapply_if_changed,
+    write_atomic,
+};
+
+// A shared endpoint + key that profiles refer to by name through a
+// model-level "credential" field, so rotating a key is a single edit.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Credential {
+    pub base_url: String,
+    pub api_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+}
+
+pub(crate) type CredentialRegistry = BTreeMap<String, Credential>;
+
+pub(crate) fn credentials_path() -> PathBuf {
+    app_dir().join("credentials.json")
+}
+
+pub(crate) fn load_credentials() -> CredentialRegistry {
+    fs::read_to_string(credentials_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save_credentials(registry: &CredentialRegistry) -> Result<(), String> {
+    let path = credentials_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let content = serde_json::to_string_pretty(registry).map_err(|e| e.to_string())?;
+    write_atomic(&path, &content)
+}
+
+// Fill in baseUrl/apiKey/provider from the referenced credential.
+// Fields set explicitly on the model take precedence over the credential.
+pub(crate) fn expand_credential(model: &Value, registry: &CredentialRegistry) -> Result<Value, String> {
+    let name = match model.get("credential").and_then(|v| v.as_str()) {
+        Some(name) => name,
+        None => return Ok(model.clone()),
+    };
+    let credential = registry
+        .get(name)
+        .ok_or_else(|| format!("Unknown credential: {}", name))?;
+
+    let mut expanded = model.clone();
+    let obj = match expanded.as_object_mut() {
+        Some(obj) => obj,
+        None => return Ok(model.clone()),
+    };
+    obj.remove("credential");
+
+    let has_field = |obj: &serde_json::Map<String, Value>, keys: &[&str]| {
+        keys.iter().any(|k| {
+            obj.get(*k)
+                .and_then(|v| v.as_str())
+                .map(|s| !s.is_empty())
+                .unwrap_or(false)
+        })
+    };
+
+    // Drop empty placeholders so the converter doesn't pick them up first
+    for key in ["baseUrl", "base_url", "apiKey", "api_key"] {
+        if obj.get(key).and_then(|v| v.as_str()) == Some("") {
+            obj.remove(key);
+        }
+    }
+
+    if !has_field(obj, &["baseUrl", "base_url"]) {
+        obj.insert("baseUrl".to_string(), Value::String(credential.base_url.clone()));
+    }
+    if !has_field(obj, &["apiKey", "api_key"]) {
+        obj.insert("apiKey".to_string(), Value::String(credential.api_key.clone()));
+    }
+    if let Some(provider) = &credential.provider {
+        if !has_field(obj, &["provider"]) {
+            obj.insert("provider".to_string(), Value::String(provider.clone()));
+        }
+    }
+    Ok(expanded)
+}
+
+// Names of profiles that reference the given credential
+fn credential_users(name: &str) -> Vec<String> {
+    profile_paths()
+        .into_iter()
+        .filter(|path| {
+            fs::read_to_string(path)
+                .ok()
+                .and_then(|s| serde_json::from_str::<Value>(&s).ok())
+                .as_ref()
+                .and_then(model_list)
+                .map(|models| {
+                    models
+                        .iter()
+                        .any(|m| m.get("credential").and_then(|v| v.as_str()) == Some(name))
+                })
+                .unwrap_or(false)
+        })
+        .map(|path| profile_id(&path))
+        .collect()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn list_credentials() -> CredentialRegistry {
+    load_credentials()
+}
+
+// Add or replace a credential. The active profile is re-applied if it uses it,
+// so a rotated key reaches settings.json right away.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn save_credential(name: String, credential: Credential) -> Result<(), String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Credential name cannot be empty".to_string());
+    }
+    let active = get_current_config();
+    let mut registry = load_credentials();
+    registry.insert(name, credential);
+    save_credentials(&registry)?;
+    reapply_if_changed(active)?;
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn delete_credential(name: String) -> Result<(), String> {
+    let users = credential_users(&name);
+    if !users.is_empty() {
+        return Err(format!("Credential is used by: {}", users.join(", ")));
+    }
+    let mut registry = load_credentials();
+    if registry.remove(&name).is_none() {
+        return Err("Credential not found".to_string());
+    }
+    save_credentials(&registry)
+}
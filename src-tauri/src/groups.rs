@@ -0,0 +1,101 @@
+use std::{fs, path::PathBuf};
+
+use crate::{
+    existing_profile, history, inherit, json_text, meta, profile_file_stem, profile_group,
+    read_profile, resolve_profile, schema, unique_profile_id, update_app_settings, write_atomic,
+    MAX_GROUP_DEPTH,
+};
+
+// Turn a user-supplied group path like "Work / EU" into safe folder names.
+// An empty group means the top level of configs_dir(). "." and ".." are
+// rejected rather than renamed, since they look like relative paths.
+pub(crate) fn normalize_group(group: &str) -> Result<Option<String>, String> {
+    let parts: Vec<&str> = group
+        .split(['/', '\\'])
+        .map(|p| p.trim())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.iter().any(|p| *p == "." || *p == "..") {
+        return Err(format!("Invalid group: {}", group));
+    }
+    let parts: Vec<String> = parts.into_iter().map(profile_file_stem).collect();
+    if parts.is_empty() {
+        return Ok(None);
+    }
+    if parts.len() > MAX_GROUP_DEPTH {
+        return Err(format!("Groups can be nested at most {} levels deep", MAX_GROUP_DEPTH));
+    }
+    Ok(Some(parts.join("/")))
+}
+
+// Folder of a group, checked the same way as a profile inside it would be
+fn group_dir(group: &str) -> Result<PathBuf, String> {
+    let placeholder = resolve_profile(&format!("{}/_", group))?;
+    Ok(placeholder.parent().ok_or("Invalid group")?.to_path_buf())
+}
+
+// Carry order position, history and usage stats over to a profile's new ID
+fn rekey_profile(old_id: &str, new_id: &str) -> Result<(), String> {
+    update_app_settings(|s| {
+        for id in s.config_order.iter_mut() {
+            if id == old_id {
+                *id = new_id.to_string();
+            }
+        }
+    })?;
+    history::move_history(old_id, new_id)?;
+    meta::move_usage(old_id, new_id)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn create_group(group: String) -> Result<String, String> {
+    let group = normalize_group(&group)?.ok_or("Group name cannot be empty")?;
+    fs::create_dir_all(group_dir(&group)?).map_err(|e| e.to_string())?;
+    Ok(group)
+}
+
+// Only empty groups can be deleted; profiles have to be moved or deleted first
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn delete_group(group: String) -> Result<(), String> {
+    let group = normalize_group(&group)?.ok_or("Group name cannot be empty")?;
+    let dir = group_dir(&group)?;
+    if fs::read_dir(&dir).map_err(|e| e.to_string())?.next().is_some() {
+        return Err("Group is not empty".to_string());
+    }
+    fs::remove_dir(&dir).map_err(|e| e.to_string())
+}
+
+// Move a profile into another group (None for the top level); returns its new ID
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn move_config(id: String, group: Option<String>) -> Result<String, String> {
+    let path = existing_profile(&id)?;
+    let group = group.map(|g| normalize_group(&g)).transpose()?.flatten();
+    if profile_group(&id) == group {
+        return Ok(id);
+    }
+    // Moving changes the ID, which profiles extending this one refer to
+    inherit::check_no_children(&id)?;
+
+    let stem = id.rsplit('/').next().unwrap_or(&id);
+    let new_id = unique_profile_id(group.as_deref(), stem)?;
+    let new_path = resolve_profile(&new_id)?;
+    if let Some(parent) = new_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&path, &new_path).map_err(|e| e.to_string())?;
+    rekey_profile(&id, &new_id)?;
+    // The schema reference is relative, so it depends on the folder depth.
+    // Only that one value is rewritten, and only if the profile has one.
+    if let Ok(mut config) = read_profile(&new_path) {
+        if config.get("$schema").is_some() {
+            schema::stamp_schema(&mut config, group.as_deref())?;
+            let content = fs::read_to_string(&new_path).map_err(|e| e.to_string())?;
+            let output = json_text::set_root_key(&content, "$schema", &config["$schema"])?;
+            write_atomic(&new_path, &output)?;
+        }
+    }
+    Ok(new_id)
+}
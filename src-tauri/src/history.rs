@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::{BTreeSet, HashMap}, fs, path::PathBuf};
+
+use crate::{
+    app_dir, convert_model, existing_profile, get_current_config, mask_key, model_list,
+    reapply_if_changed, write_atomic,
+};
+
+// One saved state of a profile
+#[derive(Serialize, Deserialize)]
+struct Revision {
+    revision: String,
+    timestamp: u64,
+    message: Option<String>,
+    content: String,
+}
+
+#[derive(Serialize)]
+pub struct RevisionInfo {
+    revision: String,
+    timestamp: u64,
+    message: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct FieldChange {
+    pub(crate) field: String,
+    pub(crate) old: Option<Value>,
+    pub(crate) new: Option<Value>,
+}
+
+#[derive(Serialize)]
+pub struct ModelDiff {
+    pub(crate) display_name: String,
+    // "added", "removed" or "changed"
+    pub(crate) status: String,
+    pub(crate) changes: Vec<FieldChange>,
+}
+
+// The suffix keeps a profile's history apart from that of a same-named group's profiles
+fn history_dir(id: &str) -> PathBuf {
+    app_dir().join("history").join(format!("{}.history", id))
+}
+
+fn now_millis() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis()
+}
+
+// Revisions of a profile, oldest first
+fn revisions(id: &str) -> Vec<Revision> {
+    let mut revisions: Vec<Revision> = fs::read_dir(history_dir(id))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| fs::read_to_string(e.path()).ok())
+                .filter_map(|s| serde_json::from_str(&s).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    revisions.sort_by(|a, b| a.revision.cmp(&b.revision));
+    revisions
+}
+
+fn load_revision(id: &str, revision: &str) -> Result<Revision, String> {
+    revisions(id)
+        .into_iter()
+        .find(|r| r.revision == revision)
+        .ok_or_else(|| format!("Revision not found: {}", revision))
+}
+
+fn write_revision(id: &str, content: &str, message: Option<&str>) -> Result<(), String> {
+    let dir = history_dir(id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    // Zero-padded so revision IDs sort chronologically as strings
+    let base = format!("{:016}", now_millis());
+    let mut revision = base.clone();
+    let mut n = 1;
+    while dir.join(format!("{}.json", revision)).exists() {
+        revision = format!("{}-{}", base, n);
+        n += 1;
+    }
+    let entry = Revision {
+        revision: revision.clone(),
+        timestamp: (now_millis() / 1000) as u64,
+        message: message.map(|m| m.trim().to_string()).filter(|m| !m.is_empty()),
+        content: content.to_string(),
+    };
+    let output = serde_json::to_string_pretty(&entry).map_err(|e| e.to_string())?;
+    fs::write(dir.join(format!("{}.json", revision)), output).map_err(|e| e.to_string())
+}
+
+// Record a save. The first time a profile is saved, its previous content is
+// kept as a baseline so the very first edit can be reverted too.
+pub(crate) fn record_revision(
+    id: &str,
+    previous: Option<&str>,
+    content: &str,
+    message: Option<&str>,
+) -> Result<(), String> {
+    let existing = revisions(id);
+    match existing.last() {
+        Some(last) if last.content == content => return Ok(()),
+        None => {
+            if let Some(previous) = previous.filter(|p| *p != content) {
+                write_revision(id, previous, Some("Before first recorded save"))?;
+            }
+        }
+        _ => {}
+    }
+    write_revision(id, content, message)
+}
+
+// Keep a profile's history when its ID changes (e.g. moved to another group)
+pub(crate) fn move_history(old_id: &str, new_id: &str) -> Result<(), String> {
+    let old_dir = history_dir(old_id);
+    if !old_dir.exists() {
+        return Ok(());
+    }
+    let new_dir = history_dir(new_id);
+    if let Some(parent) = new_dir.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::rename(&old_dir, &new_dir).map_err(|e| e.to_string())
+}
+
+// Models keyed by display name (duplicates get a "#n" suffix), in Factory format
+fn keyed_models(models: &[Value]) -> Vec<(String, Value)> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    models
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let model = convert_model(m, i);
+            let name = model.get("displayName").and_then(|v| v.as_str()).unwrap_or("").to_string();
+            let count = seen.entry(name.clone()).or_insert(0);
+            *count += 1;
+            let key = if *count > 1 { format!("{} #{}", name, count) } else { name };
+            (key, model)
+        })
+        .collect()
+}
+
+fn display_value(field: &str, value: Option<&Value>) -> Option<Value> {
+    value.map(|v| match (field, v.as_str()) {
+        ("apiKey", Some(key)) => Value::String(mask_key(key)),
+        _ => v.clone(),
+    })
+}
+
+fn field_changes(old: Option<&Value>, new: Option<&Value>) -> Vec<FieldChange> {
+    let fields: BTreeSet<&String> = old
+        .and_then(|m| m.as_object())
+        .into_iter()
+        .chain(new.and_then(|m| m.as_object()))
+        .flat_map(|obj| obj.keys())
+        .collect();
+    fields
+        .into_iter()
+        // Position-derived fields change whenever models are reordered
+        .filter(|field| *field != "id" && *field != "index")
+        .filter_map(|field| {
+            let a = old.and_then(|m| m.get(field));
+            let b = new.and_then(|m| m.get(field));
+            (a != b).then(|| FieldChange {
+                field: field.clone(),
+                old: display_value(field, a),
+                new: display_value(field, b),
+            })
+        })
+        .collect()
+}
+
+// Model-by-model differences between two model lists in either format
+pub(crate) fn diff_models(old: &[Value], new: &[Value]) -> Vec<ModelDiff> {
+    let old_models = keyed_models(old);
+    let new_models = keyed_models(new);
+    let mut diffs = Vec::new();
+
+    for (name, old_model) in &old_models {
+        match new_models.iter().find(|(n, _)| n == name) {
+            Some((_, new_model)) => {
+                let changes = field_changes(Some(old_model), Some(new_model));
+                if !changes.is_empty() {
+                    diffs.push(ModelDiff { display_name: name.clone(), status: "changed".to_string(), changes });
+                }
+            }
+            None => diffs.push(ModelDiff {
+                display_name: name.clone(),
+                status: "removed".to_string(),
+                changes: field_changes(Some(old_model), None),
+            }),
+        }
+    }
+    for (name, new_model) in &new_models {
+        if !old_models.iter().any(|(n, _)| n == name) {
+            diffs.push(ModelDiff {
+                display_name: name.clone(),
+                status: "added".to_string(),
+                changes: field_changes(None, Some(new_model)),
+            });
+        }
+    }
+    diffs
+}
+
+fn diff_contents(old: &str, new: &str) -> Result<Vec<ModelDiff>, String> {
+    let models = |content: &str| -> Result<Vec<Value>, String> {
+        let config: Value = serde_json::from_str(content).map_err(|e| e.to_string())?;
+        Ok(model_list(&config).cloned().unwrap_or_default())
+    };
+    Ok(diff_models(&models(old)?, &models(new)?))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn list_revisions(id: String) -> Result<Vec<RevisionInfo>, String> {
+    existing_profile(&id)?;
+    Ok(revisions(&id)
+        .into_iter()
+        .rev()
+        .map(|r| RevisionInfo { revision: r.revision, timestamp: r.timestamp, message: r.message })
+        .collect())
+}
+
+// Model-by-model diff between two revisions; without `to`, against the current file
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn diff_revisions(id: String, from: String, to: Option<String>) -> Result<Vec<ModelDiff>, String> {
+    let path = existing_profile(&id)?;
+    let old = load_revision(&id, &from)?.content;
+    let new = match to {
+        Some(to) => load_revision(&id, &to)?.content,
+        None => fs::read_to_string(&path).map_err(|e| e.to_string())?,
+    };
+    diff_contents(&old, &new)
+}
+
+// Restore an old revision as a new save, re-applying it if the profile is active
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn revert_revision(id: String, revision: String) -> Result<(), String> {
+    let path = existing_profile(&id)?;
+    let target = load_revision(&id, &revision)?;
+    // The active profile may be this one or one extending it
+    let active = get_current_config();
+    let previous = fs::read_to_string(&path).ok();
+
+    write_atomic(&path, &target.content)?;
+    let message = format!("Revert to {}", revision);
+    record_revision(&id, previous.as_deref(), &target.content, Some(&message))?;
+    reapply_if_changed(active)?;
+    Ok(())
+}
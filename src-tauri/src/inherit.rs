@@ -0,0 +1,164 @@
+use serde_json::{json, Map, Value};
+
+use crate::{
+    catalog::{self, Catalog},
+    existing_profile, is_factory_format, model_list, profile_id, profile_paths, read_profile,
+};
+
+// Longest chain of "extends" we follow before assuming something is wrong
+const MAX_EXTENDS_DEPTH: usize = 8;
+
+// Simplified-format keys and their Factory-format equivalents
+const KEY_ALIASES: &[(&str, &str)] = &[
+    ("model_display_name", "displayName"),
+    ("base_url", "baseUrl"),
+    ("api_key", "apiKey"),
+    ("max_tokens", "maxOutputTokens"),
+];
+
+// Factory spelling of a model key ("base_url" -> "baseUrl"); other keys are returned as is
+pub(crate) fn factory_key(key: &str) -> &str {
+    KEY_ALIASES
+        .iter()
+        .find(|(simple, _)| *simple == key)
+        .map(|(_, factory)| *factory)
+        .unwrap_or(key)
+}
+
+// Parent profile IDs from "extends": a single ID or a list of them
+fn parent_ids(config: &Value) -> Vec<String> {
+    match config.get("extends") {
+        Some(Value::String(id)) => vec![id.clone()],
+        Some(Value::Array(ids)) => ids
+            .iter()
+            .filter_map(|v| v.as_str())
+            .map(|s| s.to_string())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// Profiles whose "extends" names the given one
+fn children(id: &str) -> Vec<String> {
+    profile_paths()
+        .into_iter()
+        .filter(|path| {
+            read_profile(path)
+                .map(|config| parent_ids(&config).iter().any(|p| p == id))
+                .unwrap_or(false)
+        })
+        .map(|path| profile_id(&path))
+        .collect()
+}
+
+// Refuse to delete or move a profile that others extend, as that would break them
+pub(crate) fn check_no_children(id: &str) -> Result<(), String> {
+    let children = children(id);
+    if !children.is_empty() {
+        return Err(format!("Profile is extended by: {}", children.join(", ")));
+    }
+    Ok(())
+}
+
+// Rename simplified keys to their Factory names so models written in either
+// format can be merged field by field
+pub(crate) fn canonical_model(model: &Value) -> Value {
+    let obj = match model.as_object() {
+        Some(obj) => obj,
+        None => return model.clone(),
+    };
+    let mut canonical = Map::new();
+    for (key, value) in obj {
+        if factory_key(key) != key {
+            canonical.insert(factory_key(key).to_string(), value.clone());
+        } else if key == "supports_images" {
+            if let Some(supports) = value.as_bool() {
+                canonical.insert("noImageSupport".to_string(), json!(!supports));
+            }
+        } else if !canonical.contains_key(key) {
+            canonical.insert(key.clone(), value.clone());
+        }
+    }
+    Value::Object(canonical)
+}
+
+fn display_name(model: &Value) -> Option<&str> {
+    model.get("displayName").and_then(|v| v.as_str())
+}
+
+// Add models to the list; one with the same displayName as an existing model
+// overrides that model's fields instead of being appended
+fn merge_models(models: &mut Vec<Value>, overrides: &[Value]) {
+    for model in overrides.iter().map(canonical_model) {
+        let existing = display_name(&model)
+            .and_then(|name| models.iter_mut().find(|m| display_name(m) == Some(name)));
+        match (existing, model.as_object()) {
+            (Some(Value::Object(target)), Some(fields)) => {
+                for (key, value) in fields {
+                    target.insert(key.clone(), value.clone());
+                }
+            }
+            _ => models.push(model),
+        }
+    }
+}
+
+fn resolve_with_chain(
+    config: &Value,
+    catalog: &Catalog,
+    chain: &mut Vec<String>,
+) -> Result<Vec<Value>, String> {
+    let own = model_list(config)
+        .map(|models| models.iter().map(|m| catalog::expand_ref(m, catalog)).collect())
+        .transpose()?
+        .unwrap_or_default();
+    let parents = parent_ids(config);
+    if parents.is_empty() {
+        return Ok(own);
+    }
+
+    let mut models = Vec::new();
+    for parent in parents {
+        if chain.contains(&parent) {
+            return Err(format!("Circular extends: {} -> {}", chain.join(" -> "), parent));
+        }
+        if chain.len() >= MAX_EXTENDS_DEPTH {
+            return Err("extends chain is too deep".to_string());
+        }
+        let parent_config = read_profile(&existing_profile(&parent)?)
+            .map_err(|e| format!("Cannot read parent profile {}: {}", parent, e))?;
+        chain.push(parent);
+        let inherited = resolve_with_chain(&parent_config, catalog, chain)?;
+        chain.pop();
+        merge_models(&mut models, &inherited);
+    }
+
+    // "removeModels" drops inherited models by displayName, id or model
+    if let Some(removed) = config.get("removeModels").and_then(|v| v.as_array()) {
+        let removed: Vec<&str> = removed.iter().filter_map(|v| v.as_str()).collect();
+        models.retain(|m| {
+            !["displayName", "id", "model"].iter().any(|key| {
+                m.get(*key)
+                    .and_then(|v| v.as_str())
+                    .map(|v| removed.contains(&v))
+                    .unwrap_or(false)
+            })
+        });
+    }
+
+    merge_models(&mut models, &own);
+
+    // Inherited Factory-format models keep their id but take their new position
+    for (i, model) in models.iter_mut().enumerate() {
+        if is_factory_format(model) {
+            model["index"] = json!(i);
+        }
+    }
+    Ok(models)
+}
+
+// The profile's models with catalog references expanded and its "extends"
+// parents resolved
+pub(crate) fn resolve_models(config: &Value) -> Result<Vec<Value>, String> {
+    resolve_with_chain(config, &catalog::load_catalog(), &mut Vec::new())
+}
@@ -0,0 +1,407 @@
+// Positions of values in JSON text, for error locations and for editing a file
+// without re-serializing it. Comments (// and /* */) and trailing commas are
+// tolerated, since settings.json may be hand-edited JSONC.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Byte range of every value in the text, keyed by JSON pointer ("" for the root)
+pub(crate) type Spans = HashMap<String, (usize, usize)>;
+
+// Where scanning stopped on malformed text
+pub(crate) struct ScanError {
+    pub pointer: String,
+}
+
+struct Scanner<'a> {
+    text: &'a [u8],
+    pos: usize,
+    path: Vec<String>,
+    spans: Spans,
+}
+
+// Escape a key for use in a JSON pointer (RFC 6901)
+pub(crate) fn pointer_part(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+impl Scanner<'_> {
+    fn pointer(&self) -> String {
+        self.path.iter().map(|p| format!("/{}", pointer_part(p))).collect()
+    }
+
+    fn error(&self) -> ScanError {
+        ScanError { pointer: self.pointer() }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.text.get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), ScanError> {
+        loop {
+            match (self.peek(), self.text.get(self.pos + 1)) {
+                (Some(b' ' | b'\t' | b'\n' | b'\r'), _) => self.pos += 1,
+                (Some(b'/'), Some(b'/')) => {
+                    while !matches!(self.peek(), None | Some(b'\n')) {
+                        self.pos += 1;
+                    }
+                }
+                (Some(b'/'), Some(b'*')) => {
+                    let end = self.text[self.pos + 2..]
+                        .windows(2)
+                        .position(|w| w == b"*/")
+                        .ok_or_else(|| self.error())?;
+                    self.pos += end + 4;
+                }
+                _ => return Ok(()),
+            }
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), ScanError> {
+        if self.peek() != Some(byte) {
+            return Err(self.error());
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn string(&mut self) -> Result<String, ScanError> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        loop {
+            match self.peek() {
+                None | Some(b'\n') => return Err(self.error()),
+                Some(b'\\') => self.pos += 2,
+                Some(b'"') => {
+                    self.pos += 1;
+                    let raw = std::str::from_utf8(&self.text[start..self.pos]).unwrap_or("\"\"");
+                    return serde_json::from_str(raw).map_err(|_| self.error());
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<(), ScanError> {
+        self.skip_trivia()?;
+        let start = self.pos;
+        match self.peek() {
+            Some(b'{') => {
+                self.pos += 1;
+                loop {
+                    self.skip_trivia()?;
+                    if self.peek() == Some(b'}') {
+                        self.pos += 1;
+                        break;
+                    }
+                    let key = self.string()?;
+                    self.skip_trivia()?;
+                    self.expect(b':')?;
+                    self.path.push(key);
+                    self.value()?;
+                    self.path.pop();
+                    self.skip_trivia()?;
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b'}') => {}
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'[') => {
+                self.pos += 1;
+                let mut index = 0;
+                loop {
+                    self.skip_trivia()?;
+                    if self.peek() == Some(b']') {
+                        self.pos += 1;
+                        break;
+                    }
+                    self.path.push(index.to_string());
+                    self.value()?;
+                    self.path.pop();
+                    index += 1;
+                    self.skip_trivia()?;
+                    match self.peek() {
+                        Some(b',') => self.pos += 1,
+                        Some(b']') => {}
+                        _ => return Err(self.error()),
+                    }
+                }
+            }
+            Some(b'"') => {
+                self.string()?;
+            }
+            Some(b'-' | b'0'..=b'9' | b't' | b'f' | b'n') => {
+                let literal = |c: u8| c.is_ascii_alphanumeric() || b"+-.".contains(&c);
+                while self.peek().map(literal).unwrap_or(false) {
+                    self.pos += 1;
+                }
+                let raw = std::str::from_utf8(&self.text[start..self.pos]).unwrap_or("");
+                if serde_json::from_str::<Value>(raw).is_err() {
+                    self.pos = start;
+                    return Err(self.error());
+                }
+            }
+            None => return Err(self.error()),
+            Some(_) => return Err(self.error()),
+        }
+        self.spans.insert(self.pointer(), (start, self.pos));
+        Ok(())
+    }
+}
+
+pub(crate) fn scan(text: &str) -> Result<Spans, ScanError> {
+    let mut scanner = Scanner {
+        text: text.as_bytes(),
+        pos: 0,
+        path: Vec::new(),
+        spans: Spans::new(),
+    };
+    scanner.value()?;
+    scanner.skip_trivia()?;
+    if scanner.pos < text.len() {
+        return Err(scanner.error());
+    }
+    Ok(scanner.spans)
+}
+
+// 1-based line and column (in characters) of a byte offset
+pub(crate) fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+// Blank out comments and trailing commas so serde_json can parse JSONC.
+// Offsets, lines and columns stay the same as in the original text.
+fn blank_jsonc(text: &str) -> String {
+    let mut bytes = text.as_bytes().to_vec();
+    let blank = |bytes: &mut Vec<u8>, from: usize, to: usize| {
+        for b in &mut bytes[from..to] {
+            if *b != b'\n' {
+                *b = b' ';
+            }
+        }
+    };
+    let skip_string = |bytes: &[u8], mut i: usize| {
+        i += 1;
+        while i < bytes.len() && bytes[i] != b'"' {
+            i += if bytes[i] == b'\\' { 2 } else { 1 };
+        }
+        i + 1
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], bytes.get(i + 1)) {
+            (b'"', _) => i = skip_string(&bytes, i),
+            (b'/', Some(b'/')) => {
+                let end = bytes[i..].iter().position(|b| *b == b'\n').map_or(bytes.len(), |p| i + p);
+                blank(&mut bytes, i, end);
+                i = end;
+            }
+            (b'/', Some(b'*')) => {
+                let end = bytes[i + 2..]
+                    .windows(2)
+                    .position(|w| w == b"*/")
+                    .map_or(bytes.len(), |p| i + 2 + p + 2);
+                blank(&mut bytes, i, end);
+                i = end;
+            }
+            _ => i += 1,
+        }
+    }
+
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => i = skip_string(&bytes, i),
+            b',' => {
+                let next = bytes[i + 1..].iter().find(|b| !b.is_ascii_whitespace());
+                if matches!(next, Some(b'}' | b']')) {
+                    bytes[i] = b' ';
+                }
+                i += 1;
+            }
+            _ => i += 1,
+        }
+    }
+    String::from_utf8(bytes).unwrap_or_else(|_| text.to_string())
+}
+
+// Parse JSON that may contain comments and trailing commas
+pub(crate) fn parse_jsonc(text: &str) -> Result<Value, String> {
+    serde_json::from_str(&blank_jsonc(text)).map_err(|e| e.to_string())
+}
+
+// Leading whitespace of the line an offset is on
+fn line_indent(text: &str, offset: usize) -> &str {
+    let line_start = text[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[line_start..offset];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+// Serialize a value the way the surrounding file is formatted: `unit` per level,
+// continuation lines starting at `base`. No indentation means a one-line file.
+fn format_value(value: &Value, unit: &str, base: &str, newline: &str) -> Result<String, String> {
+    if unit.is_empty() {
+        return serde_json::to_string(value).map_err(|e| e.to_string());
+    }
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    value.serialize(&mut serializer).map_err(|e| e.to_string())?;
+    let pretty = String::from_utf8(out).map_err(|e| e.to_string())?;
+    Ok(pretty.replace('\n', &format!("{}{}", newline, base)))
+}
+
+// Set a top-level key without touching the rest of the text: the old value is
+// replaced in place, or the key is added after the last one. Formatting, key
+// order and comments elsewhere in the file stay exactly as they were.
+pub(crate) fn set_root_key(text: &str, key: &str, value: &Value) -> Result<String, String> {
+    let spans = scan(text).map_err(|e| format!("Invalid JSON at {}", e.pointer))?;
+    let (root_start, _) = spans.get("").copied().unwrap_or_default();
+    if !text[root_start..].starts_with('{') {
+        return Err("Expected a JSON object".to_string());
+    }
+    let newline = if text.contains("\r\n") { "\r\n" } else { "\n" };
+
+    if let Some(&(start, end)) = spans.get(&format!("/{}", pointer_part(key))) {
+        // A top-level value is indented by exactly one level
+        let indent = line_indent(text, start);
+        let output = format_value(value, indent, indent, newline)?;
+        return Ok(format!("{}{}{}", &text[..start], output, &text[end..]));
+    }
+
+    let quoted = serde_json::to_string(key).map_err(|e| e.to_string())?;
+    let last = spans
+        .iter()
+        .filter(|(pointer, _)| pointer.matches('/').count() == 1)
+        .max_by_key(|(_, (_, end))| *end);
+    let (at, entry) = match last {
+        // Right after the last value, so a trailing comma there stays trailing
+        Some((_, &(start, end))) => {
+            let indent = line_indent(text, start);
+            let output = format_value(value, indent, indent, newline)?;
+            let (separator, colon) = if indent.is_empty() { ("", ":") } else { (newline, ": ") };
+            (end, format!(",{}{}{}{}{}", separator, indent, quoted, colon, output))
+        }
+        None => {
+            let output = format_value(value, "  ", "  ", newline)?;
+            (root_start + 1, format!("{}  {}: {}{}", newline, quoted, output, newline))
+        }
+    };
+    Ok(format!("{}{}{}", &text[..at], entry, &text[at..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn set(text: &str, value: Value) -> String {
+        set_root_key(text, "customModels", &value).unwrap()
+    }
+
+    #[test]
+    fn replaces_an_existing_key_in_place() {
+        let text = "{\n  \"a\": 1,\n  \"customModels\": [],\n  \"b\": true\n}\n";
+        assert_eq!(
+            set(text, json!([{"model": "m"}])),
+            "{\n  \"a\": 1,\n  \"customModels\": [\n    {\n      \"model\": \"m\"\n    }\n  ],\n  \"b\": true\n}\n"
+        );
+    }
+
+    #[test]
+    fn inserts_into_an_empty_object() {
+        assert_eq!(set("{}", json!([])), "{\n  \"customModels\": []\n}");
+    }
+
+    #[test]
+    fn keeps_one_line_files_on_one_line() {
+        assert_eq!(
+            set("{\"a\":1}", json!([{"model": "m"}])),
+            "{\"a\":1,\"customModels\":[{\"model\":\"m\"}]}"
+        );
+        assert_eq!(set("{\"customModels\":[1],\"a\":1}", json!([])), "{\"customModels\":[],\"a\":1}");
+    }
+
+    #[test]
+    fn keeps_a_trailing_comma_after_the_last_key() {
+        let text = "{\n  \"a\": 1,\n}";
+        let output = set(text, json!([]));
+        assert_eq!(output, "{\n  \"a\": 1,\n  \"customModels\": [],\n}");
+        assert_eq!(parse_jsonc(&output).unwrap(), json!({"a": 1, "customModels": []}));
+    }
+
+    #[test]
+    fn leaves_comments_alone() {
+        let text = "{\n  // line comment with \"quotes\"\n  \"a\": \"// not a comment\",\n  /* block\n     comment */\n  \"customModels\": [] /* after */\n}";
+        let output = set(text, json!([1]));
+        assert_eq!(
+            output,
+            "{\n  // line comment with \"quotes\"\n  \"a\": \"// not a comment\",\n  /* block\n     comment */\n  \"customModels\": [\n    1\n  ] /* after */\n}"
+        );
+        assert_eq!(
+            parse_jsonc(&output).unwrap(),
+            json!({"a": "// not a comment", "customModels": [1]})
+        );
+    }
+
+    #[test]
+    fn keeps_comment_markers_inside_strings() {
+        let text = "{\"a\": \"/* x */\", \"b\": \"//\", \"c\": \"\\\"//\",}";
+        assert_eq!(parse_jsonc(text).unwrap(), json!({"a": "/* x */", "b": "//", "c": "\"//"}));
+        let spans = scan(text).ok().unwrap();
+        assert_eq!(&text[spans["/b"].0..spans["/b"].1], "\"//\"");
+    }
+
+    #[test]
+    fn follows_tab_indentation() {
+        let text = "{\n\t\"a\": 1\n}";
+        assert_eq!(
+            set(text, json!([{"model": "m"}])),
+            "{\n\t\"a\": 1,\n\t\"customModels\": [\n\t\t{\n\t\t\t\"model\": \"m\"\n\t\t}\n\t]\n}"
+        );
+    }
+
+    #[test]
+    fn keeps_crlf_line_endings() {
+        let text = "{\r\n  \"a\": 1,\r\n  \"customModels\": []\r\n}\r\n";
+        let output = set(text, json!([{"model": "m"}]));
+        assert_eq!(
+            output,
+            "{\r\n  \"a\": 1,\r\n  \"customModels\": [\r\n    {\r\n      \"model\": \"m\"\r\n    }\r\n  ]\r\n}\r\n"
+        );
+        assert!(!output.replace("\r\n", "").contains('\n'));
+    }
+
+    // serde_json reports byte columns; validation errors report characters
+    #[test]
+    fn reports_character_columns_after_multibyte_text() {
+        use crate::validate::validate_profile_text;
+
+        let errors = validate_profile_text("{\"名字\": \"中文\" x}");
+        assert_eq!((errors[0].line, errors[0].column), (1, 13));
+        assert!(!errors[0].message.contains("column"));
+
+        let errors = validate_profile_text("{\n  \"_meta\": {\"name\": \"日本語\"}, \"customModels\": 5\n}");
+        assert_eq!(errors[0].pointer, "/customModels");
+        assert_eq!((errors[0].line, errors[0].column), (2, 45));
+    }
+
+    #[test]
+    fn rejects_non_objects() {
+        assert!(set_root_key("[]", "customModels", &json!([])).is_err());
+        assert!(set_root_key("{\"a\": }", "customModels", &json!([])).is_err());
+    }
+}
@@ -1,19 +1,80 @@
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
 use std::sync::Mutex;
 
+mod catalog;
+pub mod cli;
+mod credentials;
+mod groups;
+mod history;
+mod inherit;
+mod json_text;
+mod lint;
+mod merge;
+mod meta;
+mod models;
+mod normalize;
+mod replace;
+mod rotation;
+mod schema;
+mod search;
+mod templates;
+mod trash;
+#[cfg(feature = "tui")]
+pub mod tui;
+mod validate;
+
 #[derive(Serialize, Deserialize)]
 struct ConfigFile {
+    id: String,
     name: String,
     path: String,
+    // Subdirectory of configs_dir() the profile lives in, e.g. "work" or "work/eu"
+    group: Option<String>,
+    meta: meta::ProfileMeta,
+}
+
+// Changes applied to every model of a profile while duplicating it
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct ModelTransform {
+    base_url: Option<String>,
+    api_key: Option<String>,
+    provider: Option<String>,
+    credential: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SaveResult {
+    // False when validation failed and the save wasn't forced
+    saved: bool,
+    errors: Vec<validate::ValidationError>,
+    // Lint findings, when lint-on-save is enabled
+    diagnostics: Vec<lint::Diagnostic>,
+    // The active profile was affected and settings.json was updated
+    applied: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
 struct AppSettings {
     factory_path: Option<String>,
+    // Profile IDs in display order
     #[serde(default)]
     config_order: Vec<String>,
+    // Deleted profiles older than this are purged from the trash; 0 keeps them forever
+    #[serde(default = "default_trash_retention_days")]
+    trash_retention_days: u64,
+    // Lint rules turned off (rule ID -> false); rules not listed are on
+    #[serde(default)]
+    lint_rules: BTreeMap<String, bool>,
+    // Lint profiles when they are saved and report the findings
+    #[serde(default)]
+    lint_on_save: bool,
+}
+
+fn default_trash_retention_days() -> u64 {
+    30
 }
 
 impl Default for AppSettings {
@@ -21,46 +82,72 @@ impl Default for AppSettings {
         Self { 
             factory_path: None,
             config_order: vec![],
+            trash_retention_days: default_trash_retention_days(),
+            lint_rules: BTreeMap::new(),
+            lint_on_save: false,
         }
     }
 }
 
 static APP_SETTINGS: Mutex<Option<AppSettings>> = Mutex::new(None);
 
-fn app_settings_path() -> PathBuf {
+// dd-switch's own data directory, separate from the Factory directory
+fn app_dir() -> PathBuf {
     dirs::config_dir()
         .unwrap_or_else(|| dirs::home_dir().unwrap())
         .join("dd-switch")
-        .join("settings.json")
 }
 
-fn load_app_settings() -> AppSettings {
-    let mut settings = APP_SETTINGS.lock().unwrap();
-    if settings.is_none() {
-        let path = app_settings_path();
-        *settings = if path.exists() {
-            fs::read_to_string(&path)
-                .ok()
-                .and_then(|s| serde_json::from_str(&s).ok())
-        } else {
-            Some(AppSettings::default())
-        };
+fn app_settings_path() -> PathBuf {
+    app_dir().join("settings.json")
+}
+
+fn read_app_settings_file() -> Option<AppSettings> {
+    let path = app_settings_path();
+    if path.exists() {
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str(&s).ok())
+    } else {
+        Some(AppSettings::default())
     }
-    settings.clone().unwrap_or_default()
 }
 
-fn save_app_settings(new_settings: &AppSettings) -> Result<(), String> {
+fn write_app_settings_file(settings: &AppSettings) -> Result<(), String> {
     let path = app_settings_path();
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).map_err(|e| e.to_string())?;
     }
-    let content = serde_json::to_string_pretty(new_settings).map_err(|e| e.to_string())?;
-    fs::write(&path, content).map_err(|e| e.to_string())?;
+    let content = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    write_atomic(&path, &content)
+}
+
+fn load_app_settings() -> AppSettings {
     let mut settings = APP_SETTINGS.lock().unwrap();
-    *settings = Some(new_settings.clone());
+    if settings.is_none() {
+        *settings = read_app_settings_file();
+    }
+    settings.clone().unwrap_or_default()
+}
+
+// Read-modify-write under the lock, so concurrent commands can't drop each other's changes.
+// The closure must not call load_app_settings/update_app_settings.
+fn update_app_settings(f: impl FnOnce(&mut AppSettings)) -> Result<(), String> {
+    let mut guard = APP_SETTINGS.lock().unwrap();
+    let mut settings = guard.clone().or_else(read_app_settings_file).unwrap_or_default();
+    f(&mut settings);
+    write_app_settings_file(&settings)?;
+    *guard = Some(settings);
     Ok(())
 }
 
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
 fn factory_base_dir() -> PathBuf {
     let settings = load_app_settings();
     if let Some(custom_path) = settings.factory_path {
@@ -75,6 +162,241 @@ fn configs_dir() -> PathBuf {
     factory_base_dir().join("configs")
 }
 
+// How deep group folders may nest inside configs_dir()
+const MAX_GROUP_DEPTH: usize = 3;
+
+// A profile's ID is its path inside configs_dir() without the extension,
+// with "/" between group folders, e.g. "work/eu-gateway"
+fn profile_id(path: &Path) -> String {
+    let dir = configs_dir();
+    let rel = path
+        .strip_prefix(&dir)
+        .unwrap_or_else(|_| Path::new(path.file_name().unwrap_or_default()))
+        .with_extension("");
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+// Group part of a profile ID, if it lives in a subfolder
+fn profile_group(id: &str) -> Option<String> {
+    id.rsplit_once('/').map(|(group, _)| group.to_string())
+}
+
+// One folder or file name within an ID; no traversal, no hidden files, no drive letters
+fn valid_id_component(part: &str) -> bool {
+    !part.is_empty() && !part.starts_with('.') && !part.contains(['\\', ':', '\0'])
+}
+
+// Resolve a profile ID to its file inside configs_dir(). Anything that could
+// point elsewhere (traversal, absolute paths, symlinks leading out of the
+// directory) is rejected.
+fn resolve_profile(id: &str) -> Result<PathBuf, String> {
+    let parts: Vec<&str> = id.split('/').collect();
+    if parts.len() > MAX_GROUP_DEPTH + 1 || !parts.iter().all(|p| valid_id_component(p)) {
+        return Err(format!("Invalid profile: {}", id));
+    }
+
+    let dir = configs_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let real_dir = dir.canonicalize().map_err(|e| e.to_string())?;
+    let mut path = dir.clone();
+    for group in &parts[..parts.len() - 1] {
+        path.push(group);
+    }
+    path.push(format!("{}.json", parts[parts.len() - 1]));
+
+    // The deepest part that exists must still resolve to somewhere inside configs_dir()
+    let mut existing = path.as_path();
+    while fs::symlink_metadata(existing).is_err() && existing != dir.as_path() {
+        existing = existing.parent().unwrap_or(&dir);
+    }
+    let real = existing.canonicalize().map_err(|e| e.to_string())?;
+    if !real.starts_with(&real_dir) {
+        return Err(format!("Profile is outside the configs directory: {}", id));
+    }
+    Ok(path)
+}
+
+// Like resolve_profile, but the profile must exist
+fn existing_profile(id: &str) -> Result<PathBuf, String> {
+    let path = resolve_profile(id)?;
+    if !path.is_file() {
+        return Err(format!("Profile not found: {}", id));
+    }
+    Ok(path)
+}
+
+// Device names Windows refuses to use as file names, whatever the extension
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+// Check a user-supplied profile name and return it trimmed
+fn validate_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name cannot be empty".to_string());
+    }
+    if name.chars().count() > 100 {
+        return Err("Profile name is too long".to_string());
+    }
+    if name.chars().any(|c| c.is_control()) {
+        return Err("Profile name contains control characters".to_string());
+    }
+    Ok(name.to_string())
+}
+
+// Map a display name to a file stem that is valid on every platform.
+// Unicode (CJK, emoji) is kept; separators and reserved characters become "-".
+fn profile_file_stem(name: &str) -> String {
+    let mapped: String = name
+        .chars()
+        .map(|c| if c.is_control() || "/\\:*?\"<>|".contains(c) { '-' } else { c })
+        .collect();
+    let is_edge = |c: char| c == '.' || c.is_whitespace();
+    let truncated: String = mapped.trim_matches(is_edge).chars().take(64).collect();
+    let mut stem = truncated.trim_end_matches(is_edge).to_string();
+    if stem.is_empty() {
+        stem = "profile".to_string();
+    }
+    let base_len = stem.find('.').unwrap_or(stem.len());
+    if RESERVED_FILE_NAMES.contains(&stem[..base_len].to_ascii_uppercase().as_str()) {
+        stem.insert(base_len, '_');
+    }
+    stem
+}
+
+// File stem for a new profile that doesn't collide with an existing one
+fn unique_profile_id(group: Option<&str>, name: &str) -> Result<String, String> {
+    let stem = match group {
+        Some(group) => format!("{}/{}", group, profile_file_stem(name)),
+        None => profile_file_stem(name),
+    };
+    let mut id = stem.clone();
+    let mut n = 2;
+    while resolve_profile(&id)?.exists() {
+        id = format!("{}-{}", stem, n);
+        n += 1;
+    }
+    Ok(id)
+}
+
+fn read_profile(path: &Path) -> Result<Value, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&content).map_err(|e| e.to_string())
+}
+
+// Display name kept in the reserved "_meta" key, falling back to the ID
+fn profile_name(config: &Value, id: &str) -> String {
+    config.get("_meta")
+        .and_then(|m| m.get("name"))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| id.to_string())
+}
+
+fn set_profile_name(config: &mut Value, name: &str) -> Result<(), String> {
+    let obj = config.as_object_mut().ok_or("Profile must be a JSON object")?;
+    let meta = obj.entry("_meta").or_insert_with(|| json!({}));
+    if !meta.is_object() {
+        *meta = json!({});
+    }
+    meta["name"] = json!(name);
+    Ok(())
+}
+
+// The name itself if it's free, otherwise "name (2)", "name (3)", ...
+fn unique_profile_name(name: &str) -> String {
+    let mut candidate = name.to_string();
+    let mut n = 2;
+    while profile_name_taken(&candidate, None) {
+        candidate = format!("{} ({})", name, n);
+        n += 1;
+    }
+    candidate
+}
+
+// Whether another profile already uses this display name
+fn profile_name_taken(name: &str, except_id: Option<&str>) -> bool {
+    profile_paths().iter().any(|path| {
+        let id = profile_id(path);
+        if Some(id.as_str()) == except_id {
+            return false;
+        }
+        let config = read_profile(path).unwrap_or(Value::Null);
+        profile_name(&config, &id) == name
+    })
+}
+
+// All profile files in configs_dir() and its group folders, sorted by path.
+// Hidden entries and symlinked folders are skipped.
+fn profile_paths() -> Vec<PathBuf> {
+    fn walk(dir: &Path, depth: usize, paths: &mut Vec<PathBuf>) {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                let path = entry.path();
+                let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+                if is_dir {
+                    if depth < MAX_GROUP_DEPTH {
+                        walk(&path, depth + 1, paths);
+                    }
+                } else if path.extension().map(|e| e == "json").unwrap_or(false) {
+                    paths.push(path);
+                }
+            }
+        }
+    }
+    
+    let mut paths = Vec::new();
+    walk(&configs_dir(), 0, &mut paths);
+    paths.sort();
+    paths
+}
+
+// Write through a hidden temp file and rename, so a crash never leaves a half-written file
+fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .ok_or("Invalid path")?
+        .to_string_lossy()
+        .to_string();
+    let tmp = path.with_file_name(format!(".{}.tmp", file_name));
+    fs::write(&tmp, content).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        e.to_string()
+    })
+}
+
+// Write several files as one unit: if any write fails, the ones already
+// written are restored to their previous content
+fn write_files_atomic(changes: &[(PathBuf, String)]) -> Result<(), String> {
+    let originals: Vec<Option<String>> = changes
+        .iter()
+        .map(|(path, _)| fs::read_to_string(path).ok())
+        .collect();
+    for (i, (path, content)) in changes.iter().enumerate() {
+        if let Err(e) = write_atomic(path, content) {
+            for ((written, _), original) in changes[..i].iter().zip(&originals) {
+                match original {
+                    Some(original) => { let _ = write_atomic(written, original); }
+                    None => { let _ = fs::remove_file(written); }
+                }
+            }
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
 // Check if model object is in correct Factory format
 fn is_factory_format(model: &Value) -> bool {
     model.get("id").is_some() && 
@@ -82,6 +404,11 @@ fn is_factory_format(model: &Value) -> bool {
     model.get("displayName").is_some()
 }
 
+// Generate unique id
+fn factory_model_id(display_name: &str, index: usize) -> String {
+    format!("custom:{}-{}", display_name.replace(" ", "-"), index)
+}
+
 // Convert a single model from custom format to Factory format
 fn convert_model(model: &Value, index: usize) -> Value {
     // If already in correct format, return as-is
@@ -129,9 +456,7 @@ fn convert_model(model: &Value, index: usize) -> Value {
         .or_else(|| model.get("noImageSupport").and_then(|v| v.as_bool()))
         .unwrap_or(false);
     
-    // Generate unique id
-    let clean_name = display_name.replace(" ", "-");
-    let id = format!("custom:{}-{}", clean_name, index);
+    let id = factory_model_id(&display_name, index);
     
     json!({
         "model": model_id,
@@ -146,21 +471,72 @@ fn convert_model(model: &Value, index: usize) -> Value {
     })
 }
 
-// Convert models array, handling both custom_models and customModels
-fn convert_models(config: &Value) -> Value {
-    let models = config.get("customModels")
+// Host part of a URL ("https://user@eu.example.com:8443/v1" -> "eu.example.com")
+fn url_host(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map(|(_, r)| r).unwrap_or(url);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let host_port = authority.rsplit_once('@').map(|(_, h)| h).unwrap_or(authority);
+    let host = if host_port.starts_with('[') {
+        // IPv6 literal
+        host_port.split(']').next().map(|h| format!("{}]", h))?
+    } else {
+        host_port.split(':').next()?.to_string()
+    };
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
+// Show just enough of an API key to tell keys apart
+fn mask_key(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+// Models array of a profile, handling both custom_models and customModels
+fn model_list(config: &Value) -> Option<&Vec<Value>> {
+    config.get("customModels")
         .or_else(|| config.get("custom_models"))
         .and_then(|v| v.as_array())
-        .cloned()
-        .unwrap_or_default();
+}
+
+fn model_list_mut(config: &mut Value) -> Option<&mut Vec<Value>> {
+    let key = if config.get("customModels").is_some() { "customModels" } else { "custom_models" };
+    config.get_mut(key).and_then(|v| v.as_array_mut())
+}
+
+// Models with "enabled": false stay in the profile but are not applied
+fn model_enabled(model: &Value) -> bool {
+    model.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true)
+}
+
+// Convert models array, handling both custom_models and customModels.
+// Catalog references, parents from "extends" and credential references are
+// resolved before conversion; disabled models are left out.
+fn convert_models(config: &Value) -> Result<Value, String> {
+    let models = inherit::resolve_models(config)?;
+    let enabled: Vec<&Value> = models.iter().filter(|m| model_enabled(m)).collect();
+    let skipped = enabled.len() < models.len();
     
-    let converted: Vec<Value> = models.iter()
-        .enumerate()
-        .map(|(i, m)| convert_model(m, i))
-        .collect();
+    let registry = credentials::load_credentials();
+    let mut converted = Vec::with_capacity(enabled.len());
+    for (i, m) in enabled.into_iter().enumerate() {
+        let expanded = credentials::expand_credential(m, &registry)?;
+        let mut model = convert_model(&expanded, i);
+        if let Some(obj) = model.as_object_mut() {
+            obj.remove("enabled");
+            // Close the gaps left by disabled models
+            if skipped {
+                obj.insert("index".to_string(), json!(i));
+            }
+        }
+        converted.push(model);
+    }
     
-    json!(converted)
-
+    Ok(json!(converted))
 }
 
 fn settings_path() -> PathBuf {
@@ -180,50 +556,103 @@ fn target_path() -> PathBuf {
     }
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 fn list_configs() -> Vec<ConfigFile> {
     let dir = configs_dir();
+    // Checked before creating the directory: a missing one lists nothing, which
+    // must not be taken as every ordered profile having disappeared
+    let listed = dir.is_dir();
     fs::create_dir_all(&dir).ok();
     let mut configs = Vec::new();
-    if let Ok(entries) = fs::read_dir(&dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().map(|e| e == "json").unwrap_or(false) {
-                configs.push(ConfigFile {
-                    name: path.file_stem().unwrap().to_string_lossy().to_string(),
-                    path: path.to_string_lossy().to_string(),
-                });
-            }
-        }
+    let usage = meta::load_usage();
+    for path in profile_paths() {
+        let id = profile_id(&path);
+        let config = read_profile(&path).unwrap_or(Value::Null);
+        configs.push(ConfigFile {
+            name: profile_name(&config, &id),
+            group: profile_group(&id),
+            meta: meta::profile_meta(&id, &config, &path, &usage),
+            id,
+            path: path.to_string_lossy().to_string(),
+        });
     }
     
     // Sort by saved order, new configs go to end
     let settings = load_app_settings();
     if !settings.config_order.is_empty() {
         configs.sort_by(|a, b| {
-            let pos_a = settings.config_order.iter().position(|x| x == &a.name).unwrap_or(usize::MAX);
-            let pos_b = settings.config_order.iter().position(|x| x == &b.name).unwrap_or(usize::MAX);
-            pos_a.cmp(&pos_b)
+            let pos_a = settings.config_order.iter().position(|x| x == &a.id).unwrap_or(usize::MAX);
+            let pos_b = settings.config_order.iter().position(|x| x == &b.id).unwrap_or(usize::MAX);
+            pos_a.cmp(&pos_b).then_with(|| a.name.cmp(&b.name))
         });
     } else {
         configs.sort_by(|a, b| a.name.cmp(&b.name));
     }
+    
+    // Drop order entries for profiles whose files have disappeared
+    let stale = settings.config_order.iter().any(|id| !configs.iter().any(|c| &c.id == id));
+    if listed && stale {
+        let _ = update_app_settings(|s| {
+            s.config_order.retain(|id| configs.iter().any(|c| &c.id == id));
+        });
+    }
     configs
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 fn save_config_order(order: Vec<String>) -> Result<(), String> {
-    let mut settings = load_app_settings();
-    settings.config_order = order;
-    save_app_settings(&settings)
+    update_app_settings(|s| s.config_order = order)
 }
 
-#[tauri::command]
+// Profile IDs in the order list_configs shows them
+fn shown_order() -> Vec<String> {
+    list_configs().into_iter().map(|c| c.id).collect()
+}
+
+// Profiles missing from config_order sort after the listed ones, so a new entry
+// in a partial (or empty) order would jump ahead of them. Start from what is
+// shown instead.
+fn seed_order(settings: &mut AppSettings, shown: Vec<String>) {
+    if shown.iter().any(|id| !settings.config_order.contains(id)) {
+        settings.config_order = shown;
+    }
+}
+
+// Put a profile ID at the given position in config_order (or at the end)
+fn insert_into_order(id: &str, position: Option<usize>) -> Result<(), String> {
+    let shown = shown_order().into_iter().filter(|x| x != id).collect();
+    update_app_settings(|s| {
+        seed_order(s, shown);
+        s.config_order.retain(|x| x != id);
+        let position = position.unwrap_or(s.config_order.len()).min(s.config_order.len());
+        s.config_order.insert(position, id.to_string());
+    })
+}
+
+// Put a profile ID right after another one in config_order (or at the end)
+fn insert_after(id: &str, after: &str) -> Result<(), String> {
+    let shown = shown_order().into_iter().filter(|x| x != id).collect();
+    update_app_settings(|s| {
+        seed_order(s, shown);
+        s.config_order.retain(|x| x != id);
+        let position = s.config_order.iter().position(|x| x == after).map(|p| p + 1);
+        s.config_order.insert(position.unwrap_or(s.config_order.len()), id.to_string());
+    })
+}
+
+fn remove_from_order(id: &str) -> Result<(), String> {
+    update_app_settings(|s| s.config_order.retain(|x| x != id))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 fn get_platform() -> String {
     std::env::consts::OS.to_string()
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 fn check_droid_installed() -> Option<String> {
     // Try multiple possible paths for droid
     let paths = if cfg!(target_os = "windows") {
@@ -257,7 +686,8 @@ fn check_droid_installed() -> Option<String> {
     None
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 async fn install_droid(proxy: Option<String>) -> Result<String, String> {
     let os = std::env::consts::OS;
     let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE")).unwrap_or_default();
@@ -324,69 +754,207 @@ async fn install_droid(proxy: Option<String>) -> Result<String, String> {
     }
 }
 
-#[tauri::command]
-fn read_config(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| e.to_string())
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+fn read_config(id: String) -> Result<String, String> {
+    fs::read_to_string(existing_profile(&id)?).map_err(|e| e.to_string())
 }
 
-#[tauri::command]
-fn save_config(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, &content).map_err(|e| e.to_string())
+#[cfg_attr(feature = "gui", tauri::command)]
+// Content with validation errors is only written when `force` is set. The active
+// profile is re-applied when the saved content is valid and changes what it applies.
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+fn save_config(
+    id: String,
+    content: String,
+    message: Option<String>,
+    force: Option<bool>,
+) -> Result<SaveResult, String> {
+    let path = existing_profile(&id)?;
+    let errors = validate::validate_profile_text(&content);
+    if !errors.is_empty() && !force.unwrap_or(false) {
+        return Ok(SaveResult { saved: false, errors, diagnostics: Vec::new(), applied: false });
+    }
+    let previous = fs::read_to_string(&path).ok();
+    let active = get_current_config();
+    let mut output = content;
+
+    // Keep the existing metadata (display name etc.) and schema reference if the
+    // new content doesn't carry them
+    if let Ok(mut new_config) = serde_json::from_str::<Value>(&output) {
+        let old_config = read_profile(&path).unwrap_or(Value::Null);
+        if let Some(obj) = new_config.as_object_mut() {
+            let mut carried = false;
+            for key in ["_meta", "$schema"] {
+                if let (false, Some(value)) = (obj.contains_key(key), old_config.get(key)) {
+                    obj.insert(key.to_string(), value.clone());
+                    carried = true;
+                }
+            }
+            if carried {
+                output = serde_json::to_string_pretty(&new_config).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    write_atomic(&path, &output)?;
+    history::record_revision(&id, previous.as_deref(), &output, message.as_deref())?;
+
+    let diagnostics = match serde_json::from_str::<Value>(&output) {
+        Ok(config) if load_app_settings().lint_on_save => lint::lint_value(&config),
+        _ => Vec::new(),
+    };
+    // Re-apply when settings.json no longer matches the active profile: the
+    // saved one, or one that extends it
+    let applied = errors.is_empty() && reapply_if_changed(active)?;
+    Ok(SaveResult { saved: true, errors, diagnostics, applied })
 }
 
-#[tauri::command]
-fn create_config(name: String) -> Result<String, String> {
-    let path = configs_dir().join(format!("{}.json", name));
-    if path.exists() {
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+fn create_config(
+    name: String,
+    group: Option<String>,
+    template: Option<String>,
+) -> Result<String, String> {
+    let name = validate_profile_name(&name)?;
+    if profile_name_taken(&name, None) {
         return Err("Config already exists".to_string());
     }
-    let template = json!({
-        "customModels": []
-    });
-    let output = serde_json::to_string_pretty(&template).map_err(|e| e.to_string())?;
-    fs::write(&path, output).map_err(|e| e.to_string())?;
-    Ok(path.to_string_lossy().to_string())
+    let mut config = match template {
+        Some(template) => templates::template_content(&template)?,
+        None => json!({
+            "customModels": []
+        }),
+    };
+    let group = group.map(|g| groups::normalize_group(&g)).transpose()?.flatten();
+    let path = resolve_profile(&unique_profile_id(group.as_deref(), &name)?)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    set_profile_name(&mut config, &name)?;
+    meta::stamp_created(&mut config);
+    schema::stamp_schema(&mut config, group.as_deref())?;
+    let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    write_atomic(&path, &output)?;
+    let id = profile_id(&path);
+    insert_into_order(&id, None)?;
+    Ok(id)
 }
 
-#[tauri::command]
-fn delete_config(path: String) -> Result<(), String> {
-    fs::remove_file(&path).map_err(|e| e.to_string())
+// Set a model field under whichever spelling the model already uses
+fn set_model_field(model: &mut Value, factory_key: &str, simplified_key: &str, value: &str) {
+    if let Some(obj) = model.as_object_mut() {
+        let key = if obj.contains_key(simplified_key) { simplified_key } else { factory_key };
+        obj.insert(key.to_string(), json!(value));
+    }
 }
 
-#[tauri::command]
-fn rename_config(old_path: String, new_name: String) -> Result<String, String> {
-    let old = PathBuf::from(&old_path);
-    let new_path = old.parent()
-        .ok_or("Invalid path")?
-        .join(format!("{}.json", new_name));
+fn apply_transform(config: &mut Value, transform: &ModelTransform) {
+    if let Some(models) = model_list_mut(config) {
+        for model in models.iter_mut() {
+            if let Some(credential) = &transform.credential {
+                // Explicit fields would override the credential, so drop them
+                if let Some(obj) = model.as_object_mut() {
+                    for key in ["baseUrl", "base_url", "apiKey", "api_key"] {
+                        obj.remove(key);
+                    }
+                    obj.insert("credential".to_string(), json!(credential));
+                }
+            }
+            if let Some(base_url) = &transform.base_url {
+                set_model_field(model, "baseUrl", "base_url", base_url);
+            }
+            if let Some(api_key) = &transform.api_key {
+                set_model_field(model, "apiKey", "api_key", api_key);
+            }
+            if let Some(provider) = &transform.provider {
+                set_model_field(model, "provider", "provider", provider);
+            }
+        }
+    }
+}
+
+// Copy a profile in one step: "name (copy)", "name (copy 2)", ... placed right
+// after the original, with its metadata and an optional transform applied
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+fn duplicate_config(id: String, transform: Option<ModelTransform>) -> Result<String, String> {
+    let source = existing_profile(&id)?;
+    let mut config = read_profile(&source)?;
+    let name = profile_name(&config, &id);
+    
+    let mut new_name = format!("{} (copy)", name);
+    let mut n = 2;
+    while profile_name_taken(&new_name, None) {
+        new_name = format!("{} (copy {})", name, n);
+        n += 1;
+    }
+    
+    set_profile_name(&mut config, &new_name)?;
+    meta::stamp_created(&mut config);
+    schema::stamp_schema(&mut config, profile_group(&id).as_deref())?;
+    apply_transform(&mut config, &transform.unwrap_or_default());
     
-    if new_path.exists() {
+    let path = resolve_profile(&unique_profile_id(profile_group(&id).as_deref(), &new_name)?)?;
+    let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    write_atomic(&path, &output)?;
+    
+    let new_id = profile_id(&path);
+    insert_after(&new_id, &id)?;
+    Ok(new_id)
+}
+
+// Deleted profiles go to the trash and can be restored from there
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+fn delete_config(id: String) -> Result<(), String> {
+    let path = existing_profile(&id)?;
+    inherit::check_no_children(&id)?;
+    let position = shown_order().iter().position(|x| x == &id);
+    trash::move_to_trash(&id, &path, position)?;
+    remove_from_order(&id)
+}
+
+// Renaming only changes the display name; the file (and so the ID) stays put
+#[cfg_attr(feature = "gui", tauri::command)]
+fn rename_config(id: String, new_name: String) -> Result<String, String> {
+    let path = existing_profile(&id)?;
+    let new_name = validate_profile_name(&new_name)?;
+    
+    if profile_name_taken(&new_name, Some(&id)) {
         return Err("Config with this name already exists".to_string());
     }
     
-    fs::rename(&old_path, &new_path).map_err(|e| e.to_string())?;
-    Ok(new_path.to_string_lossy().to_string())
+    let mut config = read_profile(&path)?;
+    set_profile_name(&mut config, &new_name)?;
+    let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    write_atomic(&path, &output)?;
+    Ok(id)
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 fn get_app_settings() -> AppSettings {
     load_app_settings()
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 fn set_factory_path(path: String) -> Result<(), String> {
-    let mut settings = load_app_settings();
-    settings.factory_path = if path.is_empty() { None } else { Some(path) };
-    save_app_settings(&settings)
+    update_app_settings(|s| {
+        s.factory_path = if path.is_empty() { None } else { Some(path) };
+    })
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 fn check_factory_path() -> Result<bool, String> {
     let base = factory_base_dir();
     Ok(base.exists() && (base.join("settings.json").exists() || base.join("config.json").exists()))
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
 fn get_default_factory_path() -> String {
     dirs::home_dir()
         .unwrap()
@@ -395,22 +963,23 @@ fn get_default_factory_path() -> String {
         .to_string()
 }
 
-#[tauri::command]
-fn apply_config(path: String) -> Result<(), String> {
+#[cfg_attr(feature = "gui", tauri::command)]
+fn apply_config(id: String) -> Result<(), String> {
     let new_config: Value = serde_json::from_str(
-        &fs::read_to_string(&path).map_err(|e| e.to_string())?
+        &fs::read_to_string(existing_profile(&id)?).map_err(|e| e.to_string())?
     ).map_err(|e| e.to_string())?;
     
     // Auto-convert models to Factory format
-    let new_models = convert_models(&new_config);
+    let new_models = convert_models(&new_config)?;
     
     let target = target_path();
     if target.exists() {
+        // Only the customModels node changes; the rest of the file stays as the user wrote it
         let content = fs::read_to_string(&target).map_err(|e| e.to_string())?;
-        let mut settings: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
-        settings["customModels"] = new_models;
-        let output = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
-        fs::write(&target, output).map_err(|e| e.to_string())?;
+        let output = json_text::set_root_key(&content, "customModels", &new_models)?;
+        if output != content {
+            fs::write(&target, output).map_err(|e| e.to_string())?;
+        }
     } else {
         let new_settings = json!({
             "customModels": new_models
@@ -418,28 +987,51 @@ fn apply_config(path: String) -> Result<(), String> {
         let output = serde_json::to_string_pretty(&new_settings).map_err(|e| e.to_string())?;
         fs::write(&target, output).map_err(|e| e.to_string())?;
     }
+    // Usage stats are best effort and never fail an apply
+    let _ = meta::record_apply(&id);
     Ok(())
 }
 
-#[tauri::command]
+// After a change that may alter what a profile resolves to: if settings.json
+// no longer matches the profile that was active before, apply it again.
+// Returns whether it did.
+fn reapply_if_changed(active: Option<String>) -> Result<bool, String> {
+    if let Some(active) = active {
+        if get_current_config().as_deref() != Some(active.as_str()) {
+            apply_config(active)?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+// What applying a profile would change in settings.json, model by model
+fn apply_diff(id: &str) -> Result<Vec<history::ModelDiff>, String> {
+    let config = read_profile(&existing_profile(id)?)?;
+    let new_models = convert_models(&config)?;
+    let current_models = fs::read_to_string(target_path())
+        .ok()
+        .and_then(|content| json_text::parse_jsonc(&content).ok())
+        .and_then(|settings| settings.get("customModels").cloned())
+        .unwrap_or(json!([]));
+    let list = |models: &Value| models.as_array().cloned().unwrap_or_default();
+    Ok(history::diff_models(&list(&current_models), &list(&new_models)))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
 fn get_current_config() -> Option<String> {
     let current_content = fs::read_to_string(target_path()).ok()?;
-    let current: Value = serde_json::from_str(&current_content).ok()?;
+    let current = json_text::parse_jsonc(&current_content).ok()?;
     let current_models = current.get("customModels")?.as_array()?;
     
-    let dir = configs_dir();
-    if let Ok(entries) = fs::read_dir(&dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().map(|e| e == "json").unwrap_or(false) {
-                if let Ok(content) = fs::read_to_string(&path) {
-                    if let Ok(config) = serde_json::from_str::<Value>(&content) {
-                        // Convert config models to Factory format for comparison
-                        let converted_models = convert_models(&config);
-                        if let Some(models) = converted_models.as_array() {
-                            if models == current_models {
-                                return Some(path.to_string_lossy().to_string());
-                            }
+    for path in profile_paths() {
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(config) = serde_json::from_str::<Value>(&content) {
+                // Convert config models to Factory format for comparison
+                if let Ok(converted_models) = convert_models(&config) {
+                    if let Some(models) = converted_models.as_array() {
+                        if models == current_models {
+                            return Some(profile_id(&path));
                         }
                     }
                 }
@@ -449,23 +1041,30 @@ fn get_current_config() -> Option<String> {
     None
 }
 
-#[tauri::command]
+#[cfg_attr(feature = "gui", tauri::command)]
 fn import_current() -> Result<String, String> {
     let content = fs::read_to_string(target_path()).map_err(|e| e.to_string())?;
-    let settings: Value = serde_json::from_str(&content).map_err(|e| e.to_string())?;
+    let settings = json_text::parse_jsonc(&content)?;
     
-    let export_config = json!({
+    let mut export_config = json!({
         "customModels": settings.get("customModels").cloned().unwrap_or(json!([]))
     });
+    meta::stamp_created(&mut export_config);
+    schema::stamp_schema(&mut export_config, None)?;
     
-    let name = format!("imported_{}", std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH).unwrap().as_secs());
-    let path = configs_dir().join(format!("{}.json", name));
+    // Two imports within the same second get distinct IDs
+    let name = format!("imported_{}", unix_now());
+    let path = resolve_profile(&unique_profile_id(None, &name)?)?;
     let output = serde_json::to_string_pretty(&export_config).map_err(|e| e.to_string())?;
-    fs::write(&path, output).map_err(|e| e.to_string())?;
-    Ok(path.to_string_lossy().to_string())
+    write_atomic(&path, &output)?;
+    let id = profile_id(&path);
+    insert_into_order(&id, None)?;
+    Ok(id)
 }
 
+// The desktop app: tray menu and window. Left out with --no-default-features,
+// which builds only the command-line front ends.
+#[cfg(feature = "gui")]
 use tauri::{
     Emitter, Manager,
     tray::TrayIconBuilder,
@@ -473,49 +1072,149 @@ use tauri::{
     WindowEvent,
 };
 
-fn build_tray_menu(app: &tauri::App) -> Result<Menu<tauri::Wry>, Box<dyn std::error::Error>> {
-    let configs = list_configs();
-    let current = get_current_config();
+// Profile name in the tray, prefixed with its icon if it has one
+#[cfg(feature = "gui")]
+fn tray_title(cfg: &ConfigFile) -> String {
+    match &cfg.meta.icon {
+        Some(icon) => format!("{} {}", icon, cfg.name),
+        None => cfg.name.clone(),
+    }
+}
+
+// Own models of a profile with whether each is enabled, for the tray toggles
+#[cfg(feature = "gui")]
+fn tray_models(id: &str) -> Vec<(String, bool)> {
+    let config = existing_profile(id).and_then(|p| read_profile(&p)).unwrap_or(Value::Null);
+    model_list(&config)
+        .map(|models| {
+            models
+                .iter()
+                .map(|m| {
+                    let name = ["displayName", "model_display_name", "ref"]
+                        .iter()
+                        .find_map(|k| m.get(*k).and_then(|v| v.as_str()))
+                        .unwrap_or("Unknown")
+                        .to_string();
+                    (name, model_enabled(m))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Add one group level of the tray: subgroups as nested submenus first, then its profiles
+#[cfg(feature = "gui")]
+fn append_tray_group(
+    app: &tauri::AppHandle,
+    parent: &Submenu<tauri::Wry>,
+    configs: &[ConfigFile],
+    group: Option<&str>,
+    current: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut subgroups: Vec<String> = Vec::new();
+    for cfg in configs {
+        let rest = match (cfg.group.as_deref(), group) {
+            (Some(g), None) => Some(g),
+            (Some(g), Some(prefix)) => g.strip_prefix(prefix).and_then(|r| r.strip_prefix('/')),
+            (None, _) => None,
+        };
+        if let Some(rest) = rest {
+            let child = rest.split('/').next().unwrap_or(rest);
+            let full = match group {
+                Some(prefix) => format!("{}/{}", prefix, child),
+                None => child.to_string(),
+            };
+            if !subgroups.contains(&full) {
+                subgroups.push(full);
+            }
+        }
+    }
+    subgroups.sort();
     
-    // Create config submenu items
-    let mut config_items: Vec<MenuItem<tauri::Wry>> = Vec::new();
-    for cfg in &configs {
-        let label = if Some(cfg.path.clone()) == current {
-            format!("✓ {}", cfg.name)
+    for subgroup in &subgroups {
+        let title = subgroup.rsplit('/').next().unwrap_or(subgroup);
+        let submenu = Submenu::new(app, title, true)?;
+        append_tray_group(app, &submenu, configs, Some(subgroup), current)?;
+        parent.append(&submenu)?;
+    }
+    
+    for cfg in configs.iter().filter(|c| c.group.as_deref() == group) {
+        let label = if Some(cfg.id.as_str()) == current {
+            format!("✓ {}", tray_title(cfg))
         } else {
-            format!("  {}", cfg.name)
+            format!("  {}", tray_title(cfg))
         };
-        let item = MenuItem::with_id(app, &format!("config:{}", cfg.path), &label, true, None::<&str>)?;
-        config_items.push(item);
+        let item = MenuItem::with_id(app, format!("config:{}", cfg.id), &label, true, None::<&str>)?;
+        parent.append(&item)?;
     }
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+fn build_tray_menu(app: &tauri::AppHandle) -> Result<Menu<tauri::Wry>, Box<dyn std::error::Error>> {
+    let configs = list_configs();
+    let current = get_current_config();
     
     let show_item = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
     let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
     
-    if config_items.is_empty() {
+    if configs.is_empty() {
         Ok(Menu::with_items(app, &[&show_item, &PredefinedMenuItem::separator(app)?, &quit_item])?)
     } else {
-        let config_refs: Vec<&MenuItem<tauri::Wry>> = config_items.iter().collect();
-        let configs_submenu = Submenu::with_items(app, "切换配置", true, &config_refs.iter().map(|i| *i as &dyn tauri::menu::IsMenuItem<tauri::Wry>).collect::<Vec<_>>())?;
-        Ok(Menu::with_items(app, &[&configs_submenu, &PredefinedMenuItem::separator(app)?, &show_item, &quit_item])?)
+        let configs_submenu = Submenu::new(app, "切换配置", true)?;
+        append_tray_group(app, &configs_submenu, &configs, None, current.as_deref())?;
+        let menu = Menu::with_items(app, &[&configs_submenu])?;
+        
+        // Enable/disable models of the active profile
+        let models = current.as_deref().map(tray_models).unwrap_or_default();
+        if !models.is_empty() {
+            let models_submenu = Submenu::new(app, "当前配置模型", true)?;
+            for (i, (name, enabled)) in models.iter().enumerate() {
+                let label = if *enabled { format!("✓ {}", name) } else { format!("  {}", name) };
+                let item = MenuItem::with_id(app, format!("model:{}", i), &label, true, None::<&str>)?;
+                models_submenu.append(&item)?;
+            }
+            menu.append(&models_submenu)?;
+        }
+        menu.append_items(&[&PredefinedMenuItem::separator(app)?, &show_item, &quit_item])?;
+        Ok(menu)
     }
 }
 
+#[cfg(feature = "gui")]
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .invoke_handler(tauri::generate_handler![
-            list_configs, read_config, save_config, create_config,
+            list_configs, read_config, save_config, create_config, duplicate_config,
             delete_config, apply_config, import_current, get_current_config,
             rename_config, get_app_settings, set_factory_path, check_factory_path,
-            get_default_factory_path, save_config_order, get_platform, install_droid, check_droid_installed
+            get_default_factory_path, save_config_order, get_platform, install_droid, check_droid_installed,
+            catalog::list_catalog, catalog::save_catalog_model, catalog::delete_catalog_model,
+            credentials::list_credentials, credentials::save_credential, credentials::delete_credential,
+            rotation::preview_key_rotation, rotation::rotate_api_key,
+            trash::list_trash, trash::restore_config, trash::purge_trash, trash::set_trash_retention,
+            history::list_revisions, history::diff_revisions, history::revert_revision,
+            meta::set_config_meta,
+            models::add_model, models::update_model, models::remove_model, models::set_model_enabled,
+            models::reorder_model, models::move_model,
+            groups::create_group, groups::delete_group, groups::move_config,
+            lint::lint_config, lint::list_lint_rules, lint::set_lint_rule, lint::set_lint_on_save,
+            merge::merge_configs, merge::split_config,
+            replace::preview_replace, replace::replace_in_profiles,
+            normalize::normalize_profiles,
+            schema::get_profile_schema,
+            search::search_configs,
+            templates::list_templates, templates::save_template, templates::delete_template
         ])
         .setup(|app| {
+            trash::purge_expired();
+            
             // Load tray icon
             let icon = app.default_window_icon().cloned().unwrap();
             
-            let menu = build_tray_menu(app)?;
+            let menu = build_tray_menu(app.handle())?;
             
             let _tray = TrayIconBuilder::with_id("main")
                 .icon(icon)
@@ -524,16 +1223,28 @@ pub fn run() {
                 .on_menu_event(|app, event| {
                     let id = event.id.as_ref();
                     if id.starts_with("config:") {
-                        let path = id.strip_prefix("config:").unwrap().to_string();
-                        let _ = apply_config(path.clone());
+                        let config_id = id.strip_prefix("config:").unwrap().to_string();
+                        let _ = apply_config(config_id.clone());
                         // Rebuild menu to update checkmarks
                         if let Some(tray) = app.tray_by_id("main") {
-                            if let Ok(new_menu) = build_tray_menu_runtime(app) {
+                            if let Ok(new_menu) = build_tray_menu(app) {
                                 let _ = tray.set_menu(Some(new_menu));
                             }
                         }
                         // Notify frontend to refresh
-                        let _ = app.emit("config-changed", path);
+                        let _ = app.emit("config-changed", config_id);
+                    } else if let Some(index) = id.strip_prefix("model:").and_then(|i| i.parse::<usize>().ok()) {
+                        if let Some(current) = get_current_config() {
+                            let enabled = tray_models(&current).get(index).map(|(_, e)| *e).unwrap_or(true);
+                            // Re-applies the profile, since it is the active one
+                            let _ = models::set_model_enabled(current.clone(), index, !enabled);
+                            if let Some(tray) = app.tray_by_id("main") {
+                                if let Ok(new_menu) = build_tray_menu(app) {
+                                    let _ = tray.set_menu(Some(new_menu));
+                                }
+                            }
+                            let _ = app.emit("config-changed", current);
+                        }
                     } else {
                         match id {
                             "show" => {
@@ -566,30 +1277,3 @@ pub fn run() {
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
-
-fn build_tray_menu_runtime(app: &tauri::AppHandle) -> Result<Menu<tauri::Wry>, Box<dyn std::error::Error>> {
-    let configs = list_configs();
-    let current = get_current_config();
-    
-    let mut config_items: Vec<MenuItem<tauri::Wry>> = Vec::new();
-    for cfg in &configs {
-        let label = if Some(cfg.path.clone()) == current {
-            format!("✓ {}", cfg.name)
-        } else {
-            format!("  {}", cfg.name)
-        };
-        let item = MenuItem::with_id(app, &format!("config:{}", cfg.path), &label, true, None::<&str>)?;
-        config_items.push(item);
-    }
-    
-    let show_item = MenuItem::with_id(app, "show", "显示窗口", true, None::<&str>)?;
-    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)?;
-    
-    if config_items.is_empty() {
-        Ok(Menu::with_items(app, &[&show_item, &PredefinedMenuItem::separator(app)?, &quit_item])?)
-    } else {
-        let config_refs: Vec<&MenuItem<tauri::Wry>> = config_items.iter().collect();
-        let configs_submenu = Submenu::with_items(app, "切换配置", true, &config_refs.iter().map(|i| *i as &dyn tauri::menu::IsMenuItem<tauri::Wry>).collect::<Vec<_>>())?;
-        Ok(Menu::with_items(app, &[&configs_submenu, &PredefinedMenuItem::separator(app)?, &show_item, &quit_item])?)
-    }
-}
@@ -0,0 +1,294 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::{
+    catalog, credentials, existing_profile, json_text::pointer_part, load_app_settings,
+    read_profile, update_app_settings, url_host,
+};
+
+// Rule IDs with what they check; every rule is on unless turned off in AppSettings
+const RULES: &[(&str, &str)] = &[
+    ("duplicate-name", "Two models share a displayName"),
+    ("duplicate-id", "Two models share an id"),
+    ("empty-api-key", "A model has no apiKey"),
+    ("base-url", "baseUrl is malformed or not HTTPS"),
+    ("max-output-tokens", "maxOutputTokens is above the known limit for the model"),
+    ("unknown-provider", "provider is not one droid knows"),
+    ("key-mismatch", "The apiKey format doesn't match the provider"),
+];
+
+const PROVIDERS: &[&str] = &["anthropic", "openai", "generic-chat-completion-api"];
+
+// Known output limits by model ID prefix; the first match wins, so more
+// specific prefixes come first
+const OUTPUT_LIMITS: &[(&str, u64)] = &[
+    ("claude-opus-4-5", 64000),
+    ("claude-opus-4", 32000),
+    ("claude-sonnet-4", 64000),
+    ("claude-haiku-4", 64000),
+    ("claude-3-7-sonnet", 64000),
+    ("claude-3-5", 8192),
+    ("claude-3", 4096),
+    ("gpt-5", 128000),
+    ("gpt-4.1", 32768),
+    ("gpt-4o", 16384),
+    ("o3", 100000),
+    ("o4-mini", 100000),
+];
+
+#[derive(Serialize, Clone)]
+pub struct Diagnostic {
+    pub(crate) rule: String,
+    // "error" or "warning"
+    pub(crate) severity: String,
+    pub(crate) message: String,
+    // JSON pointer into the profile, e.g. "/customModels/2/baseUrl"
+    pub(crate) pointer: String,
+}
+
+#[derive(Serialize)]
+pub struct LintRule {
+    id: String,
+    description: String,
+    enabled: bool,
+}
+
+struct ModelView<'a> {
+    raw: &'a Value,
+    // With catalog reference and credential filled in
+    expanded: Value,
+    pointer: String,
+}
+
+impl ModelView<'_> {
+    fn get(&self, keys: &[&str]) -> Option<&str> {
+        keys.iter().find_map(|k| self.expanded.get(*k)).and_then(|v| v.as_str())
+    }
+
+    // Pointer to the field as spelled in the profile, or to the model if the
+    // value comes from elsewhere (catalog, credential) or is missing
+    fn field_pointer(&self, keys: &[&str]) -> String {
+        match keys.iter().find(|k| self.raw.get(**k).is_some()) {
+            Some(key) => format!("{}/{}", self.pointer, pointer_part(key)),
+            None => self.pointer.clone(),
+        }
+    }
+}
+
+fn check_base_url(url: &str) -> Option<(&'static str, String)> {
+    let (scheme, rest) = match url.split_once("://") {
+        Some(parts) => parts,
+        None => return Some(("error", format!("baseUrl has no scheme: {}", url))),
+    };
+    let host = match url_host(rest) {
+        Some(host) => host,
+        None => return Some(("error", format!("baseUrl has no host: {}", url))),
+    };
+    if url.contains(char::is_whitespace) {
+        return Some(("error", "baseUrl contains whitespace".to_string()));
+    }
+    match scheme {
+        "https" => None,
+        "http" if host == "localhost" || host == "[::1]" || host.starts_with("127.") => None,
+        "http" => Some(("warning", format!("baseUrl is not HTTPS: {}", url))),
+        _ => Some(("error", format!("Unsupported baseUrl scheme: {}", scheme))),
+    }
+}
+
+fn output_limit(model_id: &str) -> Option<u64> {
+    let model_id = model_id.rsplit('/').next().unwrap_or(model_id);
+    OUTPUT_LIMITS
+        .iter()
+        .find(|(prefix, _)| model_id.starts_with(prefix))
+        .map(|(_, limit)| *limit)
+}
+
+// The provider a key was issued by, judged by its prefix
+fn key_issuer(key: &str) -> Option<&'static str> {
+    if key.starts_with("sk-ant-") {
+        Some("anthropic")
+    } else if key.starts_with("sk-or-") {
+        Some("openrouter")
+    } else if key.starts_with("sk-proj-") || key.starts_with("sk-svcacct-") {
+        Some("openai")
+    } else {
+        None
+    }
+}
+
+// Lint a parsed profile. Only the profile's own models are checked; catalog
+// references and credentials are expanded first so their values are covered.
+pub(crate) fn lint_value(config: &Value) -> Vec<Diagnostic> {
+    let rules = load_app_settings().lint_rules;
+    let mut diagnostics = Vec::new();
+    let mut push = |rule: &str, severity: &str, message: String, pointer: String| {
+        if rules.get(rule).copied().unwrap_or(true) {
+            diagnostics.push(Diagnostic {
+                rule: rule.to_string(),
+                severity: severity.to_string(),
+                message,
+                pointer,
+            });
+        }
+    };
+
+    let (key, models) = match ["customModels", "custom_models"]
+        .iter()
+        .find_map(|k| config.get(*k).and_then(|v| v.as_array()).map(|m| (*k, m)))
+    {
+        Some(found) => found,
+        None => return Vec::new(),
+    };
+    let partial = config.get("extends").is_some();
+    let catalog = catalog::load_catalog();
+    let registry = credentials::load_credentials();
+
+    let mut names: HashMap<String, usize> = HashMap::new();
+    let mut ids: HashMap<String, usize> = HashMap::new();
+    for (i, raw) in models.iter().enumerate() {
+        let expanded = catalog::expand_ref(raw, &catalog)
+            .and_then(|m| credentials::expand_credential(&m, &registry))
+            .unwrap_or_else(|_| raw.clone());
+        let model = ModelView { raw, expanded, pointer: format!("/{}/{}", key, i) };
+
+        if let Some(name) = model.get(&["model_display_name", "displayName"]) {
+            if let Some(first) = names.insert(name.to_string(), i) {
+                push(
+                    "duplicate-name",
+                    "error",
+                    format!("displayName \"{}\" is also used by model {}", name, first),
+                    model.field_pointer(&["model_display_name", "displayName"]),
+                );
+            }
+        }
+        if let Some(id) = model.get(&["id"]) {
+            if let Some(first) = ids.insert(id.to_string(), i) {
+                push(
+                    "duplicate-id",
+                    "error",
+                    format!("id \"{}\" is also used by model {}", id, first),
+                    model.field_pointer(&["id"]),
+                );
+            }
+        }
+        // Overrides in a profile that extends others only carry what they change
+        if partial {
+            continue;
+        }
+
+        let api_key = model.get(&["api_key", "apiKey"]).unwrap_or("");
+        if api_key.trim().is_empty() {
+            push(
+                "empty-api-key",
+                "error",
+                "apiKey is empty".to_string(),
+                model.field_pointer(&["api_key", "apiKey"]),
+            );
+        }
+        match model.get(&["base_url", "baseUrl"]) {
+            Some(url) => {
+                if let Some((severity, message)) = check_base_url(url) {
+                    let pointer = model.field_pointer(&["base_url", "baseUrl"]);
+                    push("base-url", severity, message, pointer);
+                }
+            }
+            None => {
+                push("base-url", "error", "baseUrl is missing".to_string(), model.pointer.clone())
+            }
+        }
+
+        let max_tokens = model.expanded.get("max_tokens")
+            .or_else(|| model.expanded.get("maxOutputTokens"))
+            .and_then(|v| v.as_u64());
+        let model_id = model.get(&["model"]).unwrap_or("");
+        if let (Some(max_tokens), Some(limit)) = (max_tokens, output_limit(model_id)) {
+            if max_tokens > limit {
+                push(
+                    "max-output-tokens",
+                    "warning",
+                    format!(
+                        "maxOutputTokens {} is above the {} limit of {}",
+                        max_tokens, model_id, limit
+                    ),
+                    model.field_pointer(&["max_tokens", "maxOutputTokens"]),
+                );
+            }
+        }
+
+        // convert_model defaults a missing provider to anthropic
+        let provider = model.get(&["provider"]).unwrap_or("anthropic");
+        if !PROVIDERS.contains(&provider) {
+            push(
+                "unknown-provider",
+                "error",
+                format!(
+                    "Unknown provider \"{}\" (expected one of {})",
+                    provider,
+                    PROVIDERS.join(", ")
+                ),
+                model.field_pointer(&["provider"]),
+            );
+        }
+        let issuer = key_issuer(api_key);
+        let mismatch = match (issuer, provider) {
+            (Some("anthropic"), "openai") | (Some("openai"), "anthropic") => true,
+            // OpenRouter keys only work against OpenRouter
+            (Some("openrouter"), _) => {
+                !model.get(&["base_url", "baseUrl"]).unwrap_or("").contains("openrouter.ai")
+            }
+            _ => false,
+        };
+        if let (true, Some(issuer)) = (mismatch, issuer) {
+            push(
+                "key-mismatch",
+                "warning",
+                format!("apiKey looks like a {} key but provider is {}", issuer, provider),
+                model.field_pointer(&["api_key", "apiKey"]),
+            );
+        }
+    }
+    diagnostics
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn lint_config(id: String) -> Result<Vec<Diagnostic>, String> {
+    let config = read_profile(&existing_profile(&id)?)?;
+    Ok(lint_value(&config))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn list_lint_rules() -> Vec<LintRule> {
+    let settings = load_app_settings();
+    RULES
+        .iter()
+        .map(|(id, description)| LintRule {
+            id: id.to_string(),
+            description: description.to_string(),
+            enabled: settings.lint_rules.get(*id).copied().unwrap_or(true),
+        })
+        .collect()
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn set_lint_rule(rule: String, enabled: bool) -> Result<(), String> {
+    if !RULES.iter().any(|(id, _)| *id == rule) {
+        return Err(format!("Unknown lint rule: {}", rule));
+    }
+    update_app_settings(|s| {
+        if enabled {
+            s.lint_rules.remove(&rule);
+        } else {
+            s.lint_rules.insert(rule, false);
+        }
+    })
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn set_lint_on_save(enabled: bool) -> Result<(), String> {
+    update_app_settings(|s| s.lint_on_save = enabled)
+}
@@ -0,0 +1,200 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use crate::{
+    credentials, existing_profile, groups, inherit, insert_after, meta,
+    models, profile_group, profile_id, profile_name, read_profile, resolve_profile, schema,
+    set_profile_name, unique_profile_id, unique_profile_name, url_host, validate_profile_name,
+    write_atomic,
+};
+
+// How merge_configs treats models that appear in more than one profile
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum Dedupe {
+    // Keep the first model for each model ID + baseUrl pair
+    ModelUrl,
+    // Keep every model, suffixing repeated display names with " (2)", " (3)", ...
+    DisplayName,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum SplitBy {
+    Provider,
+    Host,
+}
+
+// A profile's models as they would be applied (catalog references and
+// "extends" resolved), with keys in Factory spelling
+fn resolved_models(id: &str) -> Result<(Value, Vec<Value>), String> {
+    let config = read_profile(&existing_profile(id)?)?;
+    let models = inherit::resolve_models(&config)?
+        .iter()
+        .map(inherit::canonical_model)
+        .collect();
+    Ok((config, models))
+}
+
+fn text<'a>(model: &'a Value, key: &str) -> &'a str {
+    model.get(key).and_then(|v| v.as_str()).unwrap_or("")
+}
+
+// Descriptive metadata carried over to profiles made from another one
+fn inherited_meta(config: &Value) -> Value {
+    let mut meta = config.get("_meta").cloned().filter(|m| m.is_object()).unwrap_or(json!({}));
+    if let Some(obj) = meta.as_object_mut() {
+        obj.remove("name");
+        obj.remove("createdAt");
+    }
+    meta
+}
+
+fn write_new_profile(
+    group: Option<&str>,
+    name: &str,
+    mut config: Value,
+) -> Result<PathBuf, String> {
+    set_profile_name(&mut config, name)?;
+    meta::stamp_created(&mut config);
+    schema::stamp_schema(&mut config, group)?;
+    let path = resolve_profile(&unique_profile_id(group, name)?)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    write_atomic(&path, &output)?;
+    Ok(path)
+}
+
+// Write new profiles one by one; if one fails, the ones already written are removed
+fn create_profiles(
+    group: Option<&str>,
+    profiles: Vec<(String, Value)>,
+) -> Result<Vec<String>, String> {
+    let mut created = Vec::new();
+    for (name, config) in profiles {
+        match write_new_profile(group, &name, config) {
+            Ok(path) => created.push(path),
+            Err(e) => {
+                for path in &created {
+                    let _ = fs::remove_file(path);
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(created.iter().map(|p| profile_id(p)).collect())
+}
+
+// Combine the models of several profiles into a new one; returns its ID.
+// Without a dedupe rule, models with the same model ID and baseUrl are kept once.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn merge_configs(
+    ids: Vec<String>,
+    name: String,
+    dedupe: Option<Dedupe>,
+    group: Option<String>,
+) -> Result<String, String> {
+    let dedupe = dedupe.unwrap_or(Dedupe::ModelUrl);
+    if ids.len() < 2 {
+        return Err("Select at least two profiles to merge".to_string());
+    }
+    let name = validate_profile_name(&name)?;
+    let group = group.map(|g| groups::normalize_group(&g)).transpose()?.flatten();
+
+    let registry = credentials::load_credentials();
+    let mut merged: Vec<Value> = Vec::new();
+    // (model, baseUrl) of each merged model, with credentials expanded
+    let mut seen: Vec<(String, String)> = Vec::new();
+    let mut names = Vec::new();
+    for id in &ids {
+        let (config, source_models) = resolved_models(id)?;
+        names.push(profile_name(&config, id));
+        for mut model in source_models {
+            match dedupe {
+                Dedupe::ModelUrl => {
+                    // The baseUrl may come from a credential
+                    let expanded = credentials::expand_credential(&model, &registry)?;
+                    let key = (
+                        text(&expanded, "model").to_string(),
+                        text(&expanded, "baseUrl").to_string(),
+                    );
+                    if seen.contains(&key) {
+                        continue;
+                    }
+                    seen.push(key);
+                }
+                Dedupe::DisplayName => {
+                    let base = text(&model, "displayName").to_string();
+                    let mut candidate = base.clone();
+                    let mut n = 2;
+                    while merged.iter().any(|m| text(m, "displayName") == candidate) {
+                        candidate = format!("{} ({})", base, n);
+                        n += 1;
+                    }
+                    model["displayName"] = json!(candidate);
+                }
+            }
+            merged.push(model);
+        }
+    }
+
+    let mut config = json!({
+        "customModels": merged,
+        "_meta": { "description": format!("Merged from {}", names.join(", ")) },
+    });
+    models::renumber(&mut config);
+    let new_id = create_profiles(group.as_deref(), vec![(unique_profile_name(&name), config)])?
+        .remove(0);
+    insert_after(&new_id, &ids[0])?;
+    Ok(new_id)
+}
+
+// Break a profile into one new profile per provider or per baseUrl host.
+// The original is left as is; returns the new IDs.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn split_config(id: String, by: SplitBy) -> Result<Vec<String>, String> {
+    let (config, source_models) = resolved_models(&id)?;
+    let registry = credentials::load_credentials();
+
+    let mut parts: BTreeMap<String, Vec<Value>> = BTreeMap::new();
+    for model in source_models {
+        // Provider and baseUrl may come from a credential
+        let expanded = credentials::expand_credential(&model, &registry)?;
+        let key = match by {
+            SplitBy::Provider => match text(&expanded, "provider") {
+                "" => "anthropic".to_string(),
+                provider => provider.to_string(),
+            },
+            SplitBy::Host => {
+                url_host(text(&expanded, "baseUrl")).unwrap_or_else(|| "no host".to_string())
+            }
+        };
+        parts.entry(key).or_default().push(model);
+    }
+    if parts.len() < 2 {
+        return Err("All models already share the same provider or host".to_string());
+    }
+
+    let name = profile_name(&config, &id);
+    let profiles = parts
+        .into_iter()
+        .map(|(key, part_models)| {
+            let mut part = json!({ "customModels": part_models, "_meta": inherited_meta(&config) });
+            models::renumber(&mut part);
+            (unique_profile_name(&format!("{} - {}", name, key)), part)
+        })
+        .collect();
+    let new_ids = create_profiles(profile_group(&id).as_deref(), profiles)?;
+
+    let mut after = id.as_str();
+    for new_id in &new_ids {
+        insert_after(new_id, after)?;
+        after = new_id;
+    }
+    Ok(new_ids)
+}
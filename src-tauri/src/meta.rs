@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, fs, path::{Path, PathBuf}};
+
+use crate::{app_dir, existing_profile, read_profile, unix_now, write_atomic};
+
+// Metadata shown next to a profile. Description, color, icon and creation time
+// live in the profile's "_meta" key and travel with the file; usage stats are
+// per machine and live in a sidecar index so applying never rewrites a profile.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileMeta {
+    pub description: Option<String>,
+    pub color: Option<String>,
+    pub icon: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub created_at: Option<u64>,
+    pub last_applied_at: Option<u64>,
+    #[serde(default)]
+    pub apply_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Usage {
+    last_applied_at: Option<u64>,
+    apply_count: u64,
+}
+
+pub(crate) type UsageIndex = BTreeMap<String, Usage>;
+
+fn usage_path() -> PathBuf {
+    app_dir().join("usage.json")
+}
+
+pub(crate) fn load_usage() -> UsageIndex {
+    fs::read_to_string(usage_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_usage(usage: &UsageIndex) -> Result<(), String> {
+    let path = usage_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let output = serde_json::to_string_pretty(usage).map_err(|e| e.to_string())?;
+    write_atomic(&path, &output)
+}
+
+// Bump the apply stats of a profile
+pub(crate) fn record_apply(id: &str) -> Result<(), String> {
+    let mut usage = load_usage();
+    let entry = usage.entry(id.to_string()).or_default();
+    entry.last_applied_at = Some(unix_now());
+    entry.apply_count += 1;
+    save_usage(&usage)
+}
+
+// Keep a profile's stats when its ID changes
+pub(crate) fn move_usage(old_id: &str, new_id: &str) -> Result<(), String> {
+    let mut usage = load_usage();
+    match usage.remove(old_id) {
+        Some(entry) => {
+            usage.insert(new_id.to_string(), entry);
+            save_usage(&usage)
+        }
+        None => Ok(()),
+    }
+}
+
+pub(crate) fn profile_tags(config: &Value) -> Vec<String> {
+    config.get("_meta")
+        .and_then(|m| m.get("tags"))
+        .and_then(|v| v.as_array())
+        .map(|tags| {
+            tags.iter()
+                .filter_map(|t| t.as_str())
+                .map(|t| t.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// Trimmed, non-empty and unique (ignoring case), in the order given
+fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_string();
+        if tag.is_empty() || normalized.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+            continue;
+        }
+        check_meta_value("tag", &tag, 32)?;
+        normalized.push(tag);
+    }
+    if normalized.len() > 20 {
+        return Err("A profile can have at most 20 tags".to_string());
+    }
+    Ok(normalized)
+}
+
+fn meta_str(config: &Value, key: &str) -> Option<String> {
+    config.get("_meta")
+        .and_then(|m| m.get(key))
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+pub(crate) fn profile_meta(id: &str, config: &Value, path: &Path, usage: &UsageIndex) -> ProfileMeta {
+    // Profiles created before metadata existed fall back to the file's timestamps
+    let created_at = config.get("_meta")
+        .and_then(|m| m.get("createdAt"))
+        .and_then(|v| v.as_u64())
+        .or_else(|| {
+            let metadata = fs::metadata(path).ok()?;
+            let time = metadata.created().or_else(|_| metadata.modified()).ok()?;
+            time.duration_since(std::time::UNIX_EPOCH).ok().map(|d| d.as_secs())
+        });
+    let usage = usage.get(id).cloned().unwrap_or_default();
+    ProfileMeta {
+        description: meta_str(config, "description"),
+        color: meta_str(config, "color"),
+        icon: meta_str(config, "icon"),
+        tags: profile_tags(config),
+        created_at,
+        last_applied_at: usage.last_applied_at,
+        apply_count: usage.apply_count,
+    }
+}
+
+// Set _meta.createdAt to now, for freshly created profiles
+pub(crate) fn stamp_created(config: &mut Value) {
+    if let Some(obj) = config.as_object_mut() {
+        let meta = obj.entry("_meta").or_insert_with(|| json!({}));
+        if !meta.is_object() {
+            *meta = json!({});
+        }
+        meta["createdAt"] = json!(unix_now());
+    }
+}
+
+fn check_meta_value(field: &str, value: &str, max_len: usize) -> Result<(), String> {
+    if value.chars().count() > max_len {
+        return Err(format!("{} is too long", field));
+    }
+    if value.chars().any(|c| c.is_control() && c != '\n') {
+        return Err(format!("{} contains control characters", field));
+    }
+    Ok(())
+}
+
+// Update descriptive metadata. Omitted fields are left alone, empty values clear them.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn set_config_meta(
+    id: String,
+    description: Option<String>,
+    color: Option<String>,
+    icon: Option<String>,
+    tags: Option<Vec<String>>,
+) -> Result<(), String> {
+    let path = existing_profile(&id)?;
+    let mut config = read_profile(&path)?;
+    let obj = config.as_object_mut().ok_or("Profile must be a JSON object")?;
+    let meta = obj.entry("_meta").or_insert_with(|| json!({}));
+    if !meta.is_object() {
+        *meta = json!({});
+    }
+    let meta = meta.as_object_mut().unwrap();
+
+    for (field, value, max_len) in [
+        ("description", description, 500),
+        ("color", color, 32),
+        ("icon", icon, 16),
+    ] {
+        if let Some(value) = value {
+            let value = value.trim();
+            check_meta_value(field, value, max_len)?;
+            if value.is_empty() {
+                meta.remove(field);
+            } else {
+                meta.insert(field.to_string(), json!(value));
+            }
+        }
+    }
+    if let Some(tags) = tags {
+        let tags = normalize_tags(tags)?;
+        if tags.is_empty() {
+            meta.remove("tags");
+        } else {
+            meta.insert("tags".to_string(), json!(tags));
+        }
+    }
+
+    let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    write_atomic(&path, &output)
+}
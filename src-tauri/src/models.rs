@@ -0,0 +1,243 @@
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf};
+
+use crate::{
+    existing_profile, factory_model_id, get_current_config, history, is_factory_format,
+    model_list_mut, read_profile, reapply_if_changed, write_files_atomic,
+};
+
+// A profile loaded for editing, with what is needed to write it back
+struct Loaded {
+    id: String,
+    path: PathBuf,
+    previous: String,
+    config: Value,
+}
+
+fn load(id: &str) -> Result<Loaded, String> {
+    let path = existing_profile(id)?;
+    let previous = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let config = read_profile(&path)?;
+    if !config.is_object() {
+        return Err("Profile must be a JSON object".to_string());
+    }
+    Ok(Loaded { id: id.to_string(), path, previous, config })
+}
+
+// The profile's models array, created as "customModels" if missing
+fn models_mut(config: &mut Value) -> &mut Vec<Value> {
+    if model_list_mut(config).is_none() {
+        config["customModels"] = json!([]);
+    }
+    model_list_mut(config).unwrap()
+}
+
+fn has_text(model: &Value, keys: &[&str]) -> bool {
+    keys.iter()
+        .find_map(|k| model.get(*k))
+        .and_then(|v| v.as_str())
+        .map(|s| !s.trim().is_empty())
+        .unwrap_or(false)
+}
+
+// A problem with one model, and the field it is about if there is one
+pub(crate) struct ModelError {
+    pub field: Option<&'static str>,
+    pub message: String,
+}
+
+fn model_error(field: Option<&'static str>, message: String) -> ModelError {
+    ModelError { field, message }
+}
+
+// Check the fields droid needs. Models in a profile that extends others may be
+// partial overrides, so only their displayName is required there.
+pub(crate) fn check_model(config: &Value, model: &Value) -> Result<(), ModelError> {
+    let obj = model
+        .as_object()
+        .ok_or_else(|| model_error(None, "Model must be a JSON object".to_string()))?;
+    if let Some(reference) = obj.get("ref") {
+        return match reference.as_str() {
+            Some(r) if !r.trim().is_empty() => Ok(()),
+            _ => Err(model_error(Some("ref"), "ref must be a non-empty string".to_string())),
+        };
+    }
+    // The field as spelled in the model, if it is there at all
+    let present = |keys: &[&'static str]| keys.iter().copied().find(|k| obj.contains_key(*k));
+    if !has_text(model, &["model_display_name", "displayName"]) {
+        let field = present(&["model_display_name", "displayName"]);
+        return Err(model_error(field, "Model needs a display name".to_string()));
+    }
+    let partial = config.get("extends").is_some();
+    if !partial && !has_text(model, &["model"]) {
+        return Err(model_error(present(&["model"]), "Model needs a model ID".to_string()));
+    }
+    for key in ["baseUrl", "base_url", "apiKey", "api_key", "provider", "credential"] {
+        if obj.get(key).map(|v| !v.is_string()).unwrap_or(false) {
+            return Err(model_error(Some(key), format!("{} must be a string", key)));
+        }
+    }
+    for key in ["maxOutputTokens", "max_tokens"] {
+        if let Some(value) = obj.get(key) {
+            if value.as_u64().filter(|n| *n > 0).is_none() {
+                return Err(model_error(Some(key), format!("{} must be a positive integer", key)));
+            }
+        }
+    }
+    for key in ["noImageSupport", "supports_images", "enabled"] {
+        if obj.get(key).map(|v| !v.is_boolean()).unwrap_or(false) {
+            return Err(model_error(Some(key), format!("{} must be true or false", key)));
+        }
+    }
+    Ok(())
+}
+
+fn validate_model(config: &Value, model: &Value) -> Result<(), String> {
+    check_model(config, model).map_err(|e| e.message)
+}
+
+// Keep index and id of Factory-format models in line with their position
+pub(crate) fn renumber(config: &mut Value) {
+    if let Some(models) = model_list_mut(config) {
+        for (i, model) in models.iter_mut().enumerate() {
+            if !is_factory_format(model) {
+                continue;
+            }
+            let name = model["displayName"].as_str().unwrap_or("Unknown").to_string();
+            model["index"] = json!(i);
+            model["id"] = json!(factory_model_id(&name, i));
+        }
+    }
+}
+
+fn check_index(config: &mut Value, index: usize) -> Result<(), String> {
+    let len = models_mut(config).len();
+    if index >= len {
+        return Err(format!("Model index {} out of range ({} models)", index, len));
+    }
+    Ok(())
+}
+
+// Write the edited profiles together, record a revision for each and re-apply
+// the active profile if what it resolves to changed (it may extend one of them)
+fn commit(profiles: Vec<Loaded>, message: &str) -> Result<(), String> {
+    let active = get_current_config();
+    let mut changes = Vec::with_capacity(profiles.len());
+    for profile in &profiles {
+        let output = serde_json::to_string_pretty(&profile.config).map_err(|e| e.to_string())?;
+        changes.push((profile.path.clone(), output));
+    }
+    write_files_atomic(&changes)?;
+    for (profile, (_, output)) in profiles.iter().zip(&changes) {
+        history::record_revision(&profile.id, Some(&profile.previous), output, Some(message))?;
+    }
+    reapply_if_changed(active)?;
+    Ok(())
+}
+
+// Add a model at the given position (or at the end); returns its index
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn add_model(id: String, model: Value, position: Option<usize>) -> Result<usize, String> {
+    let mut profile = load(&id)?;
+    validate_model(&profile.config, &model)?;
+    let models = models_mut(&mut profile.config);
+    let position = position.unwrap_or(models.len()).min(models.len());
+    models.insert(position, model);
+    renumber(&mut profile.config);
+    commit(vec![profile], "Add model")?;
+    Ok(position)
+}
+
+// Change fields of a model. Fields set to null are removed.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn update_model(id: String, index: usize, fields: Value) -> Result<(), String> {
+    let mut profile = load(&id)?;
+    check_index(&mut profile.config, index)?;
+    let fields = fields.as_object().ok_or("Fields must be a JSON object")?;
+    let mut model = models_mut(&mut profile.config)[index].clone();
+    let obj = model.as_object_mut().ok_or("Model must be a JSON object")?;
+    for (key, value) in fields {
+        if value.is_null() {
+            obj.remove(key);
+        } else {
+            obj.insert(key.clone(), value.clone());
+        }
+    }
+    validate_model(&profile.config, &model)?;
+    models_mut(&mut profile.config)[index] = model;
+    renumber(&mut profile.config);
+    commit(vec![profile], "Update model")
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn remove_model(id: String, index: usize) -> Result<(), String> {
+    let mut profile = load(&id)?;
+    check_index(&mut profile.config, index)?;
+    models_mut(&mut profile.config).remove(index);
+    renumber(&mut profile.config);
+    commit(vec![profile], "Remove model")
+}
+
+// Disabled models stay in the profile but are left out when it is applied
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn set_model_enabled(id: String, index: usize, enabled: bool) -> Result<(), String> {
+    let mut profile = load(&id)?;
+    check_index(&mut profile.config, index)?;
+    let model = &mut models_mut(&mut profile.config)[index];
+    let obj = model.as_object_mut().ok_or("Model must be a JSON object")?;
+    if enabled {
+        obj.remove("enabled");
+    } else {
+        obj.insert("enabled".to_string(), json!(false));
+    }
+    commit(vec![profile], if enabled { "Enable model" } else { "Disable model" })
+}
+
+// Move a model to another position within the same profile
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn reorder_model(id: String, from: usize, to: usize) -> Result<(), String> {
+    let mut profile = load(&id)?;
+    check_index(&mut profile.config, from)?;
+    check_index(&mut profile.config, to)?;
+    if from == to {
+        return Ok(());
+    }
+    let models = models_mut(&mut profile.config);
+    let model = models.remove(from);
+    models.insert(to, model);
+    renumber(&mut profile.config);
+    commit(vec![profile], "Reorder models")
+}
+
+// Move a model into another profile at the given position (or at the end);
+// both profiles are written together
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn move_model(
+    id: String,
+    index: usize,
+    target_id: String,
+    position: Option<usize>,
+) -> Result<usize, String> {
+    if id == target_id {
+        return Err("Use reorder_model to move a model within a profile".to_string());
+    }
+    let mut source = load(&id)?;
+    let mut target = load(&target_id)?;
+    check_index(&mut source.config, index)?;
+    let model = models_mut(&mut source.config).remove(index);
+    validate_model(&target.config, &model)?;
+
+    let models = models_mut(&mut target.config);
+    let position = position.unwrap_or(models.len()).min(models.len());
+    models.insert(position, model);
+    renumber(&mut source.config);
+    renumber(&mut target.config);
+    commit(vec![source, target], "Move model")?;
+    Ok(position)
+}
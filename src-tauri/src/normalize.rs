@@ -0,0 +1,247 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{fs, path::PathBuf};
+
+use crate::{
+    app_dir, convert_model,
+    credentials::{self, CredentialRegistry},
+    get_current_config, history, is_factory_format, profile_id, profile_paths,
+    reapply_if_changed, unix_now, write_files_atomic,
+};
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ProfileFormat {
+    // "customModels" with Factory-format models, as written to settings.json
+    Factory,
+    // "custom_models" with model_display_name/base_url/api_key/...
+    Simplified,
+}
+
+#[derive(Serialize)]
+pub struct NormalizeReport {
+    id: String,
+    // "changed", "unchanged", "skipped" or "error"
+    status: String,
+    changes: Vec<String>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct NormalizeResult {
+    backup: Option<String>,
+    profiles: Vec<NormalizeReport>,
+}
+
+// Fields convert_model understands; anything else on a model is carried over as is
+const MODEL_FIELDS: &[&str] = &[
+    "model_display_name", "displayName", "model", "base_url", "baseUrl", "api_key", "apiKey",
+    "provider", "max_tokens", "maxOutputTokens", "supports_images", "noImageSupport", "id",
+    "index",
+];
+
+fn extra_fields(model: &Value) -> Map<String, Value> {
+    model.as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter(|(k, _)| !MODEL_FIELDS.contains(&k.as_str()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// For models using a credential, drop the defaults convert_model filled in for
+// endpoint fields the model didn't set, so the credential still supplies them.
+// Defaults for fields the credential doesn't have (e.g. no provider) are kept.
+fn keep_credential_fields(
+    converted: &mut Map<String, Value>,
+    model: &Value,
+    registry: &CredentialRegistry,
+    fields: [&str; 3],
+) {
+    let credential = match model.get("credential").and_then(|v| v.as_str()) {
+        Some(name) => match registry.get(name) {
+            Some(credential) => credential,
+            None => return,
+        },
+        None => return,
+    };
+    let supplied = [
+        !credential.base_url.is_empty(),
+        !credential.api_key.is_empty(),
+        credential.provider.is_some(),
+    ];
+    let originals: [&[&str]; 3] =
+        [&["baseUrl", "base_url"], &["apiKey", "api_key"], &["provider"]];
+    for ((field, keys), supplied) in fields.iter().zip(originals).zip(supplied) {
+        if supplied && !keys.iter().any(|k| model.get(*k).is_some()) {
+            converted.remove(*field);
+        }
+    }
+}
+
+fn to_factory(model: &Value, index: usize, registry: &CredentialRegistry) -> Value {
+    let mut converted = convert_model(model, index);
+    if let Some(obj) = converted.as_object_mut() {
+        keep_credential_fields(obj, model, registry, ["baseUrl", "apiKey", "provider"]);
+        obj.extend(extra_fields(model));
+    }
+    converted
+}
+
+fn to_simplified(model: &Value, registry: &CredentialRegistry) -> Value {
+    // Going through the Factory format first fills in the same defaults apply would
+    let factory = convert_model(model, 0);
+    let field = |key: &str| factory.get(key).cloned().unwrap_or(Value::Null);
+    let mut simplified = json!({
+        "model_display_name": field("displayName"),
+        "model": field("model"),
+        "base_url": field("baseUrl"),
+        "api_key": field("apiKey"),
+        "provider": field("provider"),
+        "max_tokens": field("maxOutputTokens"),
+        "supports_images": !factory["noImageSupport"].as_bool().unwrap_or(false),
+    });
+    if let Some(obj) = simplified.as_object_mut() {
+        keep_credential_fields(obj, model, registry, ["base_url", "api_key", "provider"]);
+        obj.extend(extra_fields(model));
+    }
+    simplified
+}
+
+// Rewrite one profile in the given format; returns what was changed
+fn normalize_profile(
+    config: &mut Value,
+    format: ProfileFormat,
+    registry: &CredentialRegistry,
+) -> Result<Vec<String>, String> {
+    let obj = config.as_object_mut().ok_or("Profile must be a JSON object")?;
+    let (key, other) = match format {
+        ProfileFormat::Factory => ("customModels", "custom_models"),
+        ProfileFormat::Simplified => ("custom_models", "customModels"),
+    };
+    let mut changes = Vec::new();
+    if let Some(models) = obj.remove(other) {
+        if obj.contains_key(key) {
+            return Err("Profile has both customModels and custom_models".to_string());
+        }
+        obj.insert(key.to_string(), models);
+        changes.push(format!("renamed {} to {}", other, key));
+    }
+
+    let models = match obj.get_mut(key).and_then(|v| v.as_array_mut()) {
+        Some(models) => models,
+        None => return Ok(changes),
+    };
+    let mut converted = 0;
+    for (i, model) in models.iter_mut().enumerate() {
+        // Catalog references are expanded when applying, not here
+        if !model.is_object() || model.get("ref").is_some() {
+            continue;
+        }
+        let normalized = match format {
+            ProfileFormat::Factory if is_factory_format(model) => continue,
+            ProfileFormat::Factory => to_factory(model, i, registry),
+            ProfileFormat::Simplified => to_simplified(model, registry),
+        };
+        if normalized != *model {
+            *model = normalized;
+            converted += 1;
+        }
+    }
+    if converted > 0 {
+        changes.push(format!("converted {} model(s)", converted));
+    }
+    Ok(changes)
+}
+
+// Copy every profile into app_dir/backups/<timestamp>, keeping group folders
+fn backup_profiles(paths: &[PathBuf]) -> Result<PathBuf, String> {
+    let base = app_dir().join("backups").join(unix_now().to_string());
+    let mut dir = base.clone();
+    let mut n = 2;
+    while dir.exists() {
+        dir = PathBuf::from(format!("{}-{}", base.to_string_lossy(), n));
+        n += 1;
+    }
+    for path in paths {
+        let target = dir.join(format!("{}.json", profile_id(path)));
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        fs::copy(path, &target).map_err(|e| e.to_string())?;
+    }
+    Ok(dir)
+}
+
+// Rewrite every profile in one format. Profiles that extend others are skipped,
+// since their models may be partial overrides. With dry_run nothing is written;
+// otherwise all profiles are backed up first and changed ones written together.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn normalize_profiles(
+    format: ProfileFormat,
+    dry_run: bool,
+) -> Result<NormalizeResult, String> {
+    let paths = profile_paths();
+    let registry = credentials::load_credentials();
+    let mut profiles = Vec::new();
+    let mut changes = Vec::new();
+    let mut revisions = Vec::new();
+
+    for path in &paths {
+        let id = profile_id(path);
+        let report = |status: &str, changes: Vec<String>, error: Option<String>| NormalizeReport {
+            id: id.clone(),
+            status: status.to_string(),
+            changes,
+            error,
+        };
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => {
+                profiles.push(report("error", Vec::new(), Some(e.to_string())));
+                continue;
+            }
+        };
+        let mut config: Value = match serde_json::from_str(&content) {
+            Ok(config) => config,
+            Err(e) => {
+                profiles.push(report("error", Vec::new(), Some(format!("Invalid JSON: {}", e))));
+                continue;
+            }
+        };
+        if config.get("extends").is_some() {
+            profiles.push(report("skipped", vec!["uses extends".to_string()], None));
+            continue;
+        }
+        match normalize_profile(&mut config, format, &registry) {
+            Ok(done) if done.is_empty() => profiles.push(report("unchanged", done, None)),
+            Ok(done) => {
+                let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+                changes.push((path.clone(), output.clone()));
+                revisions.push((id.clone(), content, output));
+                profiles.push(report("changed", done, None));
+            }
+            Err(e) => profiles.push(report("error", Vec::new(), Some(e))),
+        }
+    }
+
+    if dry_run || changes.is_empty() {
+        return Ok(NormalizeResult { backup: None, profiles });
+    }
+    let backup = backup_profiles(&paths)?;
+    let active = get_current_config();
+    write_files_atomic(&changes)?;
+    let message = match format {
+        ProfileFormat::Factory => "Normalize to Factory format",
+        ProfileFormat::Simplified => "Normalize to simplified format",
+    };
+    for (id, previous, output) in &revisions {
+        history::record_revision(id, Some(previous), output, Some(message))?;
+    }
+    // Only the spelling changed, but re-apply if the active profile no longer matches exactly
+    reapply_if_changed(active)?;
+    Ok(NormalizeResult { backup: Some(backup.to_string_lossy().to_string()), profiles })
+}
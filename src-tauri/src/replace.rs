@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{fs, path::PathBuf};
+
+use crate::{
+    catalog, credentials, get_current_config, history, inherit, mask_key, model_list_mut,
+    profile_id, profile_paths, reapply_if_changed, write_files_atomic,
+};
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchMode {
+    // The whole value has to equal the search text
+    #[default]
+    Exact,
+    // Every occurrence of the search text inside a value is replaced
+    Substring,
+}
+
+#[derive(Serialize)]
+pub struct ReplaceMatch {
+    // "profile", "credential" or "catalog"
+    source: String,
+    name: String,
+    model: Option<String>,
+    index: Option<usize>,
+    field: String,
+    old: String,
+    new: String,
+}
+
+#[derive(Serialize)]
+pub struct ReplacePreview {
+    matches: Vec<ReplaceMatch>,
+    files: usize,
+}
+
+struct ReplacePlan {
+    preview: ReplacePreview,
+    changes: Vec<(PathBuf, String)>,
+    // Profile ID, content before and after, for the history
+    revisions: Vec<(String, String, String)>,
+}
+
+struct Query<'a> {
+    field: Option<&'a str>,
+    find: &'a str,
+    replace: &'a str,
+    mode: MatchMode,
+}
+
+// API keys only ever show up masked
+fn shown(field: &str, value: &str) -> String {
+    if inherit::factory_key(field) == "apiKey" {
+        mask_key(value)
+    } else {
+        value.to_string()
+    }
+}
+
+fn model_label(model: &Value) -> Option<String> {
+    ["displayName", "model_display_name", "ref"]
+        .iter()
+        .find_map(|k| model.get(*k).and_then(|v| v.as_str()))
+        .map(|s| s.to_string())
+}
+
+// Replace matching string fields of one object; returns (field, old, new) per change.
+// A field filter matches both spellings, so "baseUrl" also finds "base_url".
+// Catalog and credential references only change when that field is asked for,
+// since the entries they name are not renamed along with them.
+fn replace_fields(object: &mut Value, query: &Query) -> Vec<(String, String, String)> {
+    let mut replaced = Vec::new();
+    let obj = match object.as_object_mut() {
+        Some(obj) => obj,
+        None => return replaced,
+    };
+    for (key, value) in obj.iter_mut() {
+        // id and index are derived from the display name and position
+        if key == "id" || key == "index" {
+            continue;
+        }
+        match query.field {
+            Some(field) if key != field && inherit::factory_key(key) != field => continue,
+            None if key == "ref" || key == "credential" => continue,
+            _ => {}
+        }
+        let old = match value.as_str() {
+            Some(old) => old.to_string(),
+            None => continue,
+        };
+        let new = match query.mode {
+            MatchMode::Exact if old == query.find => query.replace.to_string(),
+            MatchMode::Substring if old.contains(query.find) => {
+                old.replace(query.find, query.replace)
+            }
+            _ => continue,
+        };
+        if new != old {
+            *value = Value::String(new.clone());
+            replaced.push((key.clone(), old, new));
+        }
+    }
+    replaced
+}
+
+fn plan_replace(query: &Query) -> Result<ReplacePlan, String> {
+    if query.find.is_empty() {
+        return Err("Search text cannot be empty".to_string());
+    }
+    let mut matches = Vec::new();
+    let mut changes = Vec::new();
+    let mut revisions = Vec::new();
+    let mut push = |source: &str,
+                    name: &str,
+                    model: Option<String>,
+                    index: Option<usize>,
+                    found: Vec<(String, String, String)>| {
+        for (field, old, new) in found {
+            matches.push(ReplaceMatch {
+                source: source.to_string(),
+                name: name.to_string(),
+                model: model.clone(),
+                index,
+                old: shown(&field, &old),
+                new: shown(&field, &new),
+                field,
+            });
+        }
+    };
+
+    // Credentials are matched through their JSON form so field names line up
+    let mut registry = credentials::load_credentials();
+    let mut registry_changed = false;
+    for (name, credential) in registry.iter_mut() {
+        let mut value = serde_json::to_value(&*credential).map_err(|e| e.to_string())?;
+        let found = replace_fields(&mut value, query);
+        if !found.is_empty() {
+            *credential = serde_json::from_value(value).map_err(|e| e.to_string())?;
+            registry_changed = true;
+            push("credential", name, None, None, found);
+        }
+    }
+    if registry_changed {
+        let content = serde_json::to_string_pretty(&registry).map_err(|e| e.to_string())?;
+        changes.push((credentials::credentials_path(), content));
+    }
+
+    let mut catalog = catalog::load_catalog();
+    let mut catalog_changed = false;
+    for (name, model) in catalog.iter_mut() {
+        let found = replace_fields(model, query);
+        if !found.is_empty() {
+            catalog_changed = true;
+            push("catalog", name, None, None, found);
+        }
+    }
+    if catalog_changed {
+        let content = serde_json::to_string_pretty(&catalog).map_err(|e| e.to_string())?;
+        changes.push((catalog::catalog_path(), content));
+    }
+
+    for path in profile_paths() {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        let mut config: Value = match serde_json::from_str(&content) {
+            Ok(config) => config,
+            Err(_) => continue,
+        };
+        let id = profile_id(&path);
+        let mut changed = false;
+        if let Some(models) = model_list_mut(&mut config) {
+            for (i, model) in models.iter_mut().enumerate() {
+                let found = replace_fields(model, query);
+                if !found.is_empty() {
+                    changed = true;
+                    push("profile", &id, model_label(model), Some(i), found);
+                }
+            }
+        }
+        if changed {
+            let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+            changes.push((path, output.clone()));
+            revisions.push((id, content, output));
+        }
+    }
+
+    Ok(ReplacePlan {
+        preview: ReplacePreview { files: changes.len(), matches },
+        changes,
+        revisions,
+    })
+}
+
+// Every model field (or only `field`) across profiles, credentials and catalog
+// models whose value matches, with what it would become
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn preview_replace(
+    field: Option<String>,
+    find: String,
+    replace: String,
+    mode: Option<MatchMode>,
+) -> Result<ReplacePreview, String> {
+    let query = Query {
+        field: field.as_deref(),
+        find: &find,
+        replace: &replace,
+        mode: mode.unwrap_or_default(),
+    };
+    Ok(plan_replace(&query)?.preview)
+}
+
+// Apply the replacement to every match at once. Each changed profile gets a
+// history entry; settings.json is re-applied if the active profile changed.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn replace_in_profiles(
+    field: Option<String>,
+    find: String,
+    replace: String,
+    mode: Option<MatchMode>,
+) -> Result<ReplacePreview, String> {
+    let query = Query {
+        field: field.as_deref(),
+        find: &find,
+        replace: &replace,
+        mode: mode.unwrap_or_default(),
+    };
+    let plan = plan_replace(&query)?;
+    if plan.changes.is_empty() {
+        return Err("Nothing matches".to_string());
+    }
+
+    let active = get_current_config();
+    write_files_atomic(&plan.changes)?;
+    // The values themselves may be secrets, so the message only names the field
+    let message = match &field {
+        Some(field) => format!("Find and replace in {}", field),
+        None => "Find and replace".to_string(),
+    };
+    for (id, previous, output) in &plan.revisions {
+        history::record_revision(id, Some(previous), output, Some(&message))?;
+    }
+    reapply_if_changed(active)?;
+    Ok(plan.preview)
+}
@@ -0,0 +1,296 @@
+use serde::Serialize;
+use serde_json::Value;
+use std::{fs, path::{Path, PathBuf}};
+
+use crate::{
+    app_dir, catalog, convert_models, credentials, existing_profile, get_current_config,
+    model_list_mut, profile_id, profile_paths, read_profile, reapply_if_changed,
+    write_files_atomic,
+};
+
+// Copies of profiles kept outside configs/, by source name and directory under
+// app_dir: deleted profiles, normalize backups and history revisions
+const PROFILE_COPIES: [(&str, &str); 3] =
+    [("trash", "trash"), ("backup", "backups"), ("history", "history")];
+
+// Short, stable identifier for an API key (FNV-1a), so a key can be
+// referred to without pasting the secret itself
+pub(crate) fn key_fingerprint(key: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in key.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)[..12].to_string()
+}
+
+fn key_matches(key: &str, old: &str) -> bool {
+    !key.is_empty() && (key == old || key_fingerprint(key) == old)
+}
+
+fn model_key(model: &Value) -> Option<&str> {
+    model.get("apiKey")
+        .or_else(|| model.get("api_key"))
+        .and_then(|v| v.as_str())
+}
+
+// Whether a profile applies the key, through its own models, the profiles it
+// extends, credentials or catalog models
+fn applies_key(id: &str, old_key: &str) -> bool {
+    existing_profile(id)
+        .and_then(|path| read_profile(&path))
+        .and_then(|config| convert_models(&config))
+        .map(|models| {
+            models
+                .as_array()
+                .map(|models| models.iter().filter_map(model_key).any(|k| key_matches(k, old_key)))
+                .unwrap_or(false)
+        })
+        .unwrap_or(false)
+}
+
+// Replace the key in a profile's own models; returns the index and display
+// name of each model changed
+fn rotate_models(
+    config: &mut Value,
+    old_key: &str,
+    new_key: &str,
+    fingerprint: &mut Option<String>,
+) -> Vec<(usize, Option<String>)> {
+    let mut rotated = Vec::new();
+    if let Some(models) = model_list_mut(config) {
+        for (i, model) in models.iter_mut().enumerate() {
+            let key = match model_key(model) {
+                Some(key) if key_matches(key, old_key) => key.to_string(),
+                _ => continue,
+            };
+            *fingerprint = Some(key_fingerprint(&key));
+            let field = if model.get("apiKey").is_some() { "apiKey" } else { "api_key" };
+            model[field] = Value::String(new_key.to_string());
+            let name = model.get("displayName")
+                .or_else(|| model.get("model_display_name"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            rotated.push((i, name));
+        }
+    }
+    rotated
+}
+
+// Every JSON file under a directory, at any depth. Symlinks are skipped, so a
+// link loop can't recurse forever.
+fn json_files(dir: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let file_type = match entry.file_type() {
+                Ok(file_type) if !file_type.is_symlink() => file_type,
+                _ => continue,
+            };
+            let path = entry.path();
+            if file_type.is_dir() {
+                paths.extend(json_files(&path));
+            } else if path.extension().map(|e| e == "json").unwrap_or(false) {
+                paths.push(path);
+            }
+        }
+    }
+    paths.sort();
+    paths
+}
+
+#[derive(Serialize)]
+pub struct RotationMatch {
+    // "profile", "credential", "catalog", or a copy: "trash", "backup" or "history"
+    source: String,
+    name: String,
+    path: String,
+    model: Option<String>,
+    index: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct RotationPreview {
+    fingerprint: Option<String>,
+    matches: Vec<RotationMatch>,
+    active_affected: bool,
+}
+
+struct RotationPlan {
+    preview: RotationPreview,
+    changes: Vec<(PathBuf, String)>,
+    active: Option<String>,
+}
+
+// Find every profile model, credential and catalog model using the old key,
+// including old copies of profiles, and compute the rewritten files without
+// touching disk
+fn plan_rotation(old_key: &str, new_key: &str) -> Result<RotationPlan, String> {
+    let old_key = old_key.trim();
+    if old_key.is_empty() {
+        return Err("Old key cannot be empty".to_string());
+    }
+    let mut matches = Vec::new();
+    let mut changes = Vec::new();
+    let mut fingerprint = None;
+    let mut credentials_changed = false;
+
+    let mut registry = credentials::load_credentials();
+    for (name, credential) in registry.iter_mut() {
+        if key_matches(&credential.api_key, old_key) {
+            fingerprint = Some(key_fingerprint(&credential.api_key));
+            credential.api_key = new_key.to_string();
+            credentials_changed = true;
+            matches.push(RotationMatch {
+                source: "credential".to_string(),
+                name: name.clone(),
+                path: credentials::credentials_path().to_string_lossy().to_string(),
+                model: None,
+                index: None,
+            });
+        }
+    }
+    if credentials_changed {
+        let content = serde_json::to_string_pretty(&registry).map_err(|e| e.to_string())?;
+        changes.push((credentials::credentials_path(), content));
+    }
+
+    let mut catalog = catalog::load_catalog();
+    let mut catalog_changed = false;
+    for (name, model) in catalog.iter_mut() {
+        let key = match model_key(model) {
+            Some(key) if key_matches(key, old_key) => key.to_string(),
+            _ => continue,
+        };
+        fingerprint = Some(key_fingerprint(&key));
+        let field = if model.get("apiKey").is_some() { "apiKey" } else { "api_key" };
+        model[field] = Value::String(new_key.to_string());
+        catalog_changed = true;
+        matches.push(RotationMatch {
+            source: "catalog".to_string(),
+            name: name.clone(),
+            path: catalog::catalog_path().to_string_lossy().to_string(),
+            model: None,
+            index: None,
+        });
+    }
+    if catalog_changed {
+        let content = serde_json::to_string_pretty(&catalog).map_err(|e| e.to_string())?;
+        changes.push((catalog::catalog_path(), content));
+    }
+
+    let active = get_current_config();
+    let active_affected = active.as_deref().map(|id| applies_key(id, old_key)).unwrap_or(false);
+
+    for path in profile_paths() {
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        let mut config: Value = match serde_json::from_str(&content) {
+            Ok(config) => config,
+            Err(_) => continue,
+        };
+        let rotated = rotate_models(&mut config, old_key, new_key, &mut fingerprint);
+        if rotated.is_empty() {
+            continue;
+        }
+        for (index, model) in rotated {
+            matches.push(RotationMatch {
+                source: "profile".to_string(),
+                name: profile_id(&path),
+                path: path.to_string_lossy().to_string(),
+                model,
+                index: Some(index),
+            });
+        }
+        let output = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+        changes.push((path, output));
+    }
+
+    // Rewrite the copies as well, so the old key doesn't linger on disk
+    for (source, dir) in PROFILE_COPIES {
+        let root = app_dir().join(dir);
+        for path in json_files(&root) {
+            let content = match fs::read_to_string(&path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            let mut file: Value = match serde_json::from_str(&content) {
+                Ok(file) => file,
+                Err(_) => continue,
+            };
+            // History revisions hold the profile as text under "content"
+            let rotated = if source == "history" {
+                let mut config: Value = match file.get("content")
+                    .and_then(|v| v.as_str())
+                    .and_then(|text| serde_json::from_str(text).ok())
+                {
+                    Some(config) => config,
+                    None => continue,
+                };
+                let rotated = rotate_models(&mut config, old_key, new_key, &mut fingerprint);
+                if !rotated.is_empty() {
+                    let text = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+                    file["content"] = Value::String(text);
+                }
+                rotated
+            } else {
+                rotate_models(&mut file, old_key, new_key, &mut fingerprint)
+            };
+            if rotated.is_empty() {
+                continue;
+            }
+            let name = path
+                .strip_prefix(&root)
+                .unwrap_or(&path)
+                .with_extension("")
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            for (index, model) in rotated {
+                matches.push(RotationMatch {
+                    source: source.to_string(),
+                    name: name.clone(),
+                    path: path.to_string_lossy().to_string(),
+                    model,
+                    index: Some(index),
+                });
+            }
+            let output = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+            changes.push((path, output));
+        }
+    }
+
+    Ok(RotationPlan {
+        preview: RotationPreview { fingerprint, matches, active_affected },
+        changes,
+        active,
+    })
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn preview_key_rotation(old_key: String) -> Result<RotationPreview, String> {
+    Ok(plan_rotation(&old_key, "")?.preview)
+}
+
+// Replace an API key (given in full or by fingerprint) everywhere it is used,
+// including the trash, backups and history. All files are rewritten together;
+// settings.json is re-applied if the active profile was affected.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn rotate_api_key(old_key: String, new_key: String) -> Result<RotationPreview, String> {
+    let new_key = new_key.trim().to_string();
+    if new_key.is_empty() {
+        return Err("New key cannot be empty".to_string());
+    }
+    let plan = plan_rotation(&old_key, &new_key)?;
+    if plan.preview.matches.is_empty() {
+        return Err("No profile uses this key".to_string());
+    }
+    write_files_atomic(&plan.changes)?;
+    reapply_if_changed(plan.active)?;
+    Ok(plan.preview)
+}
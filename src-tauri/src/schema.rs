@@ -0,0 +1,160 @@
+// JSON Schema for profile files, generated from the types below. The schema is
+// written next to settings.json and new profiles point at it through "$schema",
+// so editors like VS Code can complete and check configs/*.json.
+//
+// Every field is optional here: catalog references and profiles that extend
+// others only carry what they change. validate.rs checks what droid needs.
+// The types are never constructed, they only describe the file format. Keep
+// them in sync with the fields models::check_model accepts.
+#![allow(dead_code)]
+
+use schemars::{gen::SchemaSettings, JsonSchema};
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf};
+
+use crate::factory_base_dir;
+
+const SCHEMA_FILE: &str = "dd-switch.profile.schema.json";
+
+#[derive(JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+enum Provider {
+    Anthropic,
+    Openai,
+    GenericChatCompletionApi,
+}
+
+/// Fields both model formats share
+#[derive(JsonSchema)]
+struct ModelReferences {
+    /// Name of a shared catalog model; the other fields override it
+    #[serde(rename = "ref")]
+    reference: Option<String>,
+    /// Name of a stored credential supplying baseUrl, apiKey and provider
+    credential: Option<String>,
+    /// Disabled models stay in the profile but are not applied
+    enabled: Option<bool>,
+}
+
+/// A model in the format droid reads from settings.json
+#[derive(JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct FactoryModel {
+    /// Name shown in droid's model picker
+    display_name: Option<String>,
+    /// Model ID sent to the provider
+    model: Option<String>,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    provider: Option<Provider>,
+    max_output_tokens: Option<u64>,
+    no_image_support: Option<bool>,
+    /// "custom:<displayName>-<index>"; filled in when the profile is applied
+    id: Option<String>,
+    /// Position in the list; filled in when the profile is applied
+    index: Option<u64>,
+    #[serde(flatten)]
+    references: ModelReferences,
+}
+
+/// A model in the simplified format, converted to the Factory format on apply
+#[derive(JsonSchema)]
+struct SimplifiedModel {
+    /// Name shown in droid's model picker
+    model_display_name: Option<String>,
+    /// Model ID sent to the provider
+    model: Option<String>,
+    base_url: Option<String>,
+    api_key: Option<String>,
+    /// Defaults to anthropic
+    provider: Option<Provider>,
+    /// Defaults to 8192
+    max_tokens: Option<u64>,
+    /// Defaults to true
+    supports_images: Option<bool>,
+    #[serde(flatten)]
+    references: ModelReferences,
+}
+
+/// Parent profile IDs whose models this profile inherits
+#[derive(JsonSchema)]
+#[serde(untagged)]
+enum Extends {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(JsonSchema)]
+#[serde(rename_all = "camelCase")]
+struct ProfileMetaFields {
+    /// Display name; the file name is the profile ID
+    name: Option<String>,
+    description: Option<String>,
+    /// CSS color, e.g. "#4f8cff"
+    color: Option<String>,
+    /// Emoji or short text shown before the name
+    icon: Option<String>,
+    tags: Option<Vec<String>>,
+    /// Unix timestamp in seconds
+    created_at: Option<u64>,
+}
+
+/// A dd-switch profile: a set of custom models applied to droid's settings.json
+#[derive(JsonSchema)]
+struct Profile {
+    #[serde(rename = "$schema")]
+    schema: Option<String>,
+    #[serde(rename = "customModels")]
+    factory_models: Option<Vec<FactoryModel>>,
+    /// Simplified spelling of customModels; use one or the other
+    #[serde(rename = "custom_models")]
+    simplified_models: Option<Vec<SimplifiedModel>>,
+    extends: Option<Extends>,
+    /// Inherited models to leave out, by displayName, id or model
+    #[serde(rename = "removeModels")]
+    remove_models: Option<Vec<String>>,
+    #[serde(rename = "_meta")]
+    meta: Option<ProfileMetaFields>,
+}
+
+pub(crate) fn profile_schema() -> Value {
+    // Optional means the key may be left out, not that it may be null
+    let generator = SchemaSettings::draft07()
+        .with(|s| s.option_add_null_type = false)
+        .into_generator();
+    serde_json::to_value(generator.into_root_schema_for::<Profile>()).unwrap_or(Value::Null)
+}
+
+fn schema_path() -> PathBuf {
+    factory_base_dir().join(SCHEMA_FILE)
+}
+
+// Write the schema file, unless it is already up to date
+fn write_schema_file() -> Result<(), String> {
+    let path = schema_path();
+    let content = serde_json::to_string_pretty(&profile_schema()).map_err(|e| e.to_string())?;
+    if fs::read_to_string(&path).ok().as_deref() == Some(content.as_str()) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::write(&path, content).map_err(|e| e.to_string())
+}
+
+// Point a new profile at the schema. The reference is relative to the profile
+// file, so it keeps working when the Factory directory is synced elsewhere.
+pub(crate) fn stamp_schema(config: &mut Value, group: Option<&str>) -> Result<(), String> {
+    write_schema_file()?;
+    let depth = group.map(|g| g.split('/').count()).unwrap_or(0);
+    if let Some(obj) = config.as_object_mut() {
+        let reference = format!("{}{}", "../".repeat(depth + 1), SCHEMA_FILE);
+        obj.insert("$schema".to_string(), json!(reference));
+    }
+    Ok(())
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+pub fn get_profile_schema() -> Value {
+    profile_schema()
+}
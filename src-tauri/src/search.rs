@@ -0,0 +1,158 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    catalog, convert_model, credentials, meta, model_list, profile_group, profile_id, profile_name,
+    profile_paths, read_profile, url_host,
+};
+
+#[derive(Serialize, Clone)]
+pub struct SearchMatch {
+    // "name", "tag", "displayName", "model" or "host"
+    field: String,
+    value: String,
+    model_index: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct SearchResult {
+    id: String,
+    name: String,
+    group: Option<String>,
+    score: u32,
+    matches: Vec<SearchMatch>,
+}
+
+struct Candidate {
+    field: &'static str,
+    value: String,
+    model_index: Option<usize>,
+    // Percentage applied to the match score, so name hits rank above host hits
+    weight: u32,
+}
+
+// How well one query term matches a value: exact > prefix > word start >
+// substring > scattered subsequence (the tighter, the better)
+fn term_score(term: &str, value: &str) -> Option<u32> {
+    let value = value.to_lowercase();
+    if value == term {
+        return Some(100);
+    }
+    if value.starts_with(term) {
+        return Some(80);
+    }
+    if let Some(pos) = value.find(term) {
+        let at_word_start = value[..pos]
+            .chars()
+            .last()
+            .map(|c| !c.is_alphanumeric())
+            .unwrap_or(true);
+        return Some(if at_word_start { 70 } else { 60 });
+    }
+
+    let term_chars: Vec<char> = term.chars().collect();
+    let mut next = 0;
+    let mut first = None;
+    let mut last = 0;
+    for (i, c) in value.chars().enumerate() {
+        if next < term_chars.len() && c == term_chars[next] {
+            first.get_or_insert(i);
+            last = i;
+            next += 1;
+        }
+    }
+    if next < term_chars.len() {
+        return None;
+    }
+    let span = (last - first.unwrap_or(0) + 1) as u32;
+    Some(20 + 20 * term_chars.len() as u32 / span.max(1))
+}
+
+fn candidates(id: &str, config: &Value) -> Vec<Candidate> {
+    let mut candidates = vec![Candidate {
+        field: "name",
+        value: profile_name(config, id),
+        model_index: None,
+        weight: 100,
+    }];
+    for tag in meta::profile_tags(config) {
+        candidates.push(Candidate { field: "tag", value: tag, model_index: None, weight: 90 });
+    }
+
+    let catalog = catalog::load_catalog();
+    let registry = credentials::load_credentials();
+    for (i, model) in model_list(config).cloned().unwrap_or_default().iter().enumerate() {
+        let model = catalog::expand_ref(model, &catalog).unwrap_or_else(|_| model.clone());
+        let model = credentials::expand_credential(&model, &registry).unwrap_or(model);
+        let model = convert_model(&model, i);
+        let field = |key: &str| model.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+        candidates.push(Candidate { field: "displayName", value: field("displayName"), model_index: Some(i), weight: 80 });
+        candidates.push(Candidate { field: "model", value: field("model"), model_index: Some(i), weight: 80 });
+        if let Some(host) = url_host(&field("baseUrl")) {
+            candidates.push(Candidate { field: "host", value: host, model_index: Some(i), weight: 70 });
+        }
+    }
+    candidates.retain(|c| !c.value.is_empty());
+    candidates
+}
+
+// Fuzzy search over profile names, tags, model display names, model IDs and
+// baseUrl hosts. Every whitespace-separated term has to match somewhere.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn search_configs(query: String) -> Vec<SearchResult> {
+    let terms: Vec<String> = query.split_whitespace().map(|t| t.to_lowercase()).collect();
+    if terms.is_empty() {
+        return Vec::new();
+    }
+
+    let mut results = Vec::new();
+    for path in profile_paths() {
+        let id = profile_id(&path);
+        let config = match read_profile(&path) {
+            Ok(config) => config,
+            Err(_) => continue,
+        };
+        let candidates = candidates(&id, &config);
+
+        let mut score = 0;
+        let mut matches: Vec<SearchMatch> = Vec::new();
+        let mut all_matched = true;
+        for term in &terms {
+            let best = candidates
+                .iter()
+                .filter_map(|c| term_score(term, &c.value).map(|s| (s * c.weight / 100, c)))
+                .max_by_key(|(s, _)| *s);
+            match best {
+                Some((term_score, candidate)) => {
+                    score += term_score;
+                    let duplicate = matches.iter().any(|m| {
+                        m.field == candidate.field && m.value == candidate.value && m.model_index == candidate.model_index
+                    });
+                    if !duplicate {
+                        matches.push(SearchMatch {
+                            field: candidate.field.to_string(),
+                            value: candidate.value.clone(),
+                            model_index: candidate.model_index,
+                        });
+                    }
+                }
+                None => {
+                    all_matched = false;
+                    break;
+                }
+            }
+        }
+        if all_matched {
+            results.push(SearchResult {
+                name: profile_name(&config, &id),
+                group: profile_group(&id),
+                id,
+                score,
+                matches,
+            });
+        }
+    }
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    results
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{fs, path::PathBuf};
+
+use crate::{app_dir, profile_file_stem, validate_profile_name, write_atomic};
+
+#[derive(Serialize)]
+pub struct Template {
+    id: String,
+    name: String,
+    description: Option<String>,
+    builtin: bool,
+    content: Value,
+}
+
+// A user template as stored in app_dir/templates/<id>.json
+#[derive(Serialize, Deserialize)]
+struct UserTemplate {
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    content: Value,
+}
+
+fn templates_dir() -> PathBuf {
+    app_dir().join("templates")
+}
+
+fn builtin(id: &str, name: &str, description: &str, models: Value) -> Template {
+    Template {
+        id: id.to_string(),
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        builtin: true,
+        content: json!({ "custom_models": models }),
+    }
+}
+
+// Starting points for common providers, in the simplified format.
+// Keys are placeholders to be replaced after creating the profile.
+fn builtin_templates() -> Vec<Template> {
+    vec![
+        builtin("empty", "Empty", "No models", json!([])),
+        builtin("anthropic", "Anthropic", "Anthropic API, direct", json!([
+            {
+                "model_display_name": "Claude Sonnet",
+                "model": "claude-sonnet-4-5",
+                "base_url": "https://api.anthropic.com",
+                "api_key": "YOUR_ANTHROPIC_API_KEY",
+                "provider": "anthropic",
+                "max_tokens": 64000,
+                "supports_images": true
+            },
+            {
+                "model_display_name": "Claude Haiku",
+                "model": "claude-haiku-4-5",
+                "base_url": "https://api.anthropic.com",
+                "api_key": "YOUR_ANTHROPIC_API_KEY",
+                "provider": "anthropic",
+                "max_tokens": 64000,
+                "supports_images": true
+            }
+        ])),
+        builtin("openai", "OpenAI", "OpenAI API, direct", json!([
+            {
+                "model_display_name": "GPT-5",
+                "model": "gpt-5",
+                "base_url": "https://api.openai.com/v1",
+                "api_key": "YOUR_OPENAI_API_KEY",
+                "provider": "openai",
+                "max_tokens": 128000,
+                "supports_images": true
+            }
+        ])),
+        builtin("openrouter", "OpenRouter", "Any model through OpenRouter", json!([
+            {
+                "model_display_name": "Claude Sonnet (OpenRouter)",
+                "model": "anthropic/claude-sonnet-4.5",
+                "base_url": "https://openrouter.ai/api/v1",
+                "api_key": "YOUR_OPENROUTER_API_KEY",
+                "provider": "generic-chat-completion-api",
+                "max_tokens": 64000,
+                "supports_images": true
+            }
+        ])),
+        builtin("openai-compatible", "OpenAI-compatible", "Any Chat Completions gateway", json!([
+            {
+                "model_display_name": "Gateway Model",
+                "model": "MODEL_ID",
+                "base_url": "https://gateway.example.com/v1",
+                "api_key": "YOUR_GATEWAY_API_KEY",
+                "provider": "generic-chat-completion-api",
+                "max_tokens": 8192,
+                "supports_images": false
+            }
+        ])),
+        builtin("ollama", "Ollama", "Local models served by Ollama", json!([
+            {
+                "model_display_name": "Ollama (local)",
+                "model": "qwen2.5-coder:32b",
+                "base_url": "http://localhost:11434/v1",
+                "api_key": "ollama",
+                "provider": "generic-chat-completion-api",
+                "max_tokens": 8192,
+                "supports_images": false
+            }
+        ])),
+        builtin("lm-studio", "LM Studio", "Local models served by LM Studio", json!([
+            {
+                "model_display_name": "LM Studio (local)",
+                "model": "MODEL_ID",
+                "base_url": "http://localhost:1234/v1",
+                "api_key": "lm-studio",
+                "provider": "generic-chat-completion-api",
+                "max_tokens": 8192,
+                "supports_images": false
+            }
+        ])),
+    ]
+}
+
+// Template files on case-insensitive file systems must not shadow built-ins either
+fn is_builtin(id: &str) -> bool {
+    builtin_templates().iter().any(|t| t.id.eq_ignore_ascii_case(id))
+}
+
+fn user_templates() -> Vec<Template> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(templates_dir())
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
+                .collect()
+        })
+        .unwrap_or_default();
+    paths.sort();
+    paths
+        .into_iter()
+        .filter_map(|path| {
+            let id = path.file_stem()?.to_string_lossy().to_string();
+            let content = fs::read_to_string(&path).ok()?;
+            let template: UserTemplate = serde_json::from_str(&content).ok()?;
+            Some(Template {
+                id,
+                name: template.name,
+                description: template.description,
+                builtin: false,
+                content: template.content,
+            })
+        })
+        .collect()
+}
+
+// Profile content of a template, built-in or user-defined
+pub(crate) fn template_content(id: &str) -> Result<Value, String> {
+    builtin_templates()
+        .into_iter()
+        .chain(user_templates())
+        .find(|t| t.id == id)
+        .map(|t| t.content)
+        .ok_or_else(|| format!("Template not found: {}", id))
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn list_templates() -> Vec<Template> {
+    let mut templates = builtin_templates();
+    templates.extend(user_templates());
+    templates
+}
+
+// Save profile content as a user template; returns the template ID.
+// Saving under an existing user template's name replaces it.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn save_template(
+    name: String,
+    description: Option<String>,
+    content: String,
+) -> Result<String, String> {
+    let name = validate_profile_name(&name)?;
+    let mut content: Value =
+        serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let obj = content.as_object_mut().ok_or("Template must be a JSON object")?;
+    // The new profile gets its own name and creation time
+    if let Some(meta) = obj.get_mut("_meta").and_then(|m| m.as_object_mut()) {
+        meta.remove("name");
+        meta.remove("createdAt");
+    }
+
+    let id = profile_file_stem(&name);
+    if is_builtin(&id) {
+        return Err("Built-in templates cannot be replaced".to_string());
+    }
+    let template = UserTemplate {
+        name,
+        description: description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty()),
+        content,
+    };
+    let dir = templates_dir();
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let output = serde_json::to_string_pretty(&template).map_err(|e| e.to_string())?;
+    write_atomic(&dir.join(format!("{}.json", id)), &output)?;
+    Ok(id)
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn delete_template(id: String) -> Result<(), String> {
+    if is_builtin(&id) {
+        return Err("Built-in templates cannot be deleted".to_string());
+    }
+    let path = templates_dir().join(format!("{}.json", profile_file_stem(&id)));
+    if !path.is_file() {
+        return Err(format!("Template not found: {}", id));
+    }
+    fs::remove_file(&path).map_err(|e| e.to_string())
+}
@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{fs, path::{Path, PathBuf}};
+
+use crate::{
+    app_dir, insert_into_order, load_app_settings, profile_group, profile_name, read_profile,
+    resolve_profile,
+    set_profile_name, unique_profile_id, unique_profile_name, unix_now, update_app_settings,
+    write_atomic,
+};
+
+const DAY_SECS: u64 = 24 * 60 * 60;
+
+// What we need to put a deleted profile back where it was
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TrashEntry {
+    trash_id: String,
+    id: String,
+    name: String,
+    order_position: Option<usize>,
+    deleted_at: u64,
+}
+
+fn trash_dir() -> PathBuf {
+    app_dir().join("trash")
+}
+
+// Trash IDs are directory names we generated; anything else is rejected
+fn trash_entry_dir(trash_id: &str) -> Result<PathBuf, String> {
+    if trash_id.is_empty() || trash_id.contains(['/', '\\', ':']) || trash_id.starts_with('.') {
+        return Err(format!("Invalid trash entry: {}", trash_id));
+    }
+    let dir = trash_dir().join(trash_id);
+    if !dir.join("entry.json").is_file() {
+        return Err(format!("Trash entry not found: {}", trash_id));
+    }
+    Ok(dir)
+}
+
+fn read_entry(dir: &Path) -> Option<TrashEntry> {
+    fs::read_to_string(dir.join("entry.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+}
+
+fn entries() -> Vec<TrashEntry> {
+    let mut entries: Vec<TrashEntry> = fs::read_dir(trash_dir())
+        .map(|dirs| dirs.flatten().filter_map(|d| read_entry(&d.path())).collect())
+        .unwrap_or_default();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_at));
+    entries
+}
+
+// Move a profile file into the trash together with its order position and metadata
+pub(crate) fn move_to_trash(id: &str, path: &Path, order_position: Option<usize>) -> Result<(), String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let config = serde_json::from_str::<Value>(&content).unwrap_or(Value::Null);
+    let deleted_at = unix_now();
+
+    let flat_id = id.replace('/', "--");
+    let mut trash_id = format!("{}-{}", deleted_at, flat_id);
+    let mut n = 2;
+    while trash_dir().join(&trash_id).exists() {
+        trash_id = format!("{}-{}-{}", deleted_at, flat_id, n);
+        n += 1;
+    }
+    let dir = trash_dir().join(&trash_id);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let entry = TrashEntry {
+        trash_id,
+        id: id.to_string(),
+        name: profile_name(&config, id),
+        order_position,
+        deleted_at,
+    };
+    fs::write(dir.join("profile.json"), &content).map_err(|e| e.to_string())?;
+    let entry_json = serde_json::to_string_pretty(&entry).map_err(|e| e.to_string())?;
+    fs::write(dir.join("entry.json"), entry_json).map_err(|e| e.to_string())?;
+
+    fs::remove_file(path).map_err(|e| {
+        let _ = fs::remove_dir_all(&dir);
+        e.to_string()
+    })
+}
+
+// Remove trash entries older than the configured retention
+pub(crate) fn purge_expired() {
+    let days = load_app_settings().trash_retention_days;
+    if days == 0 {
+        return;
+    }
+    let cutoff = unix_now().saturating_sub(days * DAY_SECS);
+    for entry in entries() {
+        if entry.deleted_at < cutoff {
+            let _ = fs::remove_dir_all(trash_dir().join(&entry.trash_id));
+        }
+    }
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn list_trash() -> Vec<TrashEntry> {
+    purge_expired();
+    entries()
+}
+
+// Put a profile back under its old ID and position. If the ID or the name has
+// been reused in the meantime, the restored profile gets a fresh one.
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn restore_config(trash_id: String) -> Result<String, String> {
+    let dir = trash_entry_dir(&trash_id)?;
+    let entry = read_entry(&dir).ok_or("Trash entry is corrupted")?;
+
+    let id = if resolve_profile(&entry.id)?.exists() {
+        let stem = entry.id.rsplit('/').next().unwrap_or(&entry.id);
+        unique_profile_id(profile_group(&entry.id).as_deref(), stem)?
+    } else {
+        entry.id.clone()
+    };
+    let path = resolve_profile(&id)?;
+    // The group folder may have been removed since
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let name = unique_profile_name(&entry.name);
+    let output = if name != entry.name {
+        let mut config = read_profile(&dir.join("profile.json"))?;
+        set_profile_name(&mut config, &name)?;
+        serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?
+    } else {
+        fs::read_to_string(dir.join("profile.json")).map_err(|e| e.to_string())?
+    };
+
+    write_atomic(&path, &output)?;
+    insert_into_order(&id, entry.order_position)?;
+    fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+// Permanently delete one trash entry, or the whole trash when no ID is given
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn purge_trash(trash_id: Option<String>) -> Result<(), String> {
+    match trash_id {
+        Some(trash_id) => fs::remove_dir_all(trash_entry_dir(&trash_id)?).map_err(|e| e.to_string()),
+        None => {
+            let dir = trash_dir();
+            if dir.exists() {
+                fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg_attr(feature = "gui", tauri::command)]
+#[cfg_attr(not(feature = "gui"), allow(dead_code))]
+pub fn set_trash_retention(days: u64) -> Result<(), String> {
+    update_app_settings(|s| s.trash_retention_days = days)?;
+    purge_expired();
+    Ok(())
+}
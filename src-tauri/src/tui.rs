@@ -0,0 +1,191 @@
+// Terminal switcher for SSH sessions: profiles on the left with the active one
+// highlighted, the selected profile's models or apply diff on the right.
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::{
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+
+use crate::{apply_config, apply_diff, cli, get_current_config, list_configs, ConfigFile};
+
+const HELP: &str = "↑/↓ select  Enter apply  d models/diff  r reload  q quit";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Pane {
+    Models,
+    // What applying the selected profile would change in settings.json
+    Diff,
+}
+
+struct App {
+    configs: Vec<ConfigFile>,
+    active: Option<String>,
+    list: ListState,
+    pane: Pane,
+    status: String,
+}
+
+impl App {
+    fn new() -> Self {
+        let mut app = App {
+            configs: Vec::new(),
+            active: None,
+            list: ListState::default(),
+            pane: Pane::Models,
+            status: String::new(),
+        };
+        app.reload();
+        // Start on the active profile
+        let active = app.configs.iter().position(|c| Some(&c.id) == app.active.as_ref());
+        app.list.select(active.or(app.list.selected()));
+        app
+    }
+
+    fn reload(&mut self) {
+        self.configs = list_configs();
+        self.active = get_current_config();
+        let last = self.configs.len().checked_sub(1);
+        let selected = self.list.selected().unwrap_or(0);
+        self.list.select(last.map(|last| selected.min(last)));
+    }
+
+    fn selected(&self) -> Option<&ConfigFile> {
+        self.list.selected().and_then(|i| self.configs.get(i))
+    }
+
+    fn move_selection(&mut self, down: bool) {
+        let (Some(i), Some(last)) = (self.list.selected(), self.configs.len().checked_sub(1)) else {
+            return;
+        };
+        self.list.select(Some(if down { (i + 1).min(last) } else { i.saturating_sub(1) }));
+    }
+
+    fn apply_selected(&mut self) {
+        let Some(config) = self.selected() else {
+            return;
+        };
+        let (id, name) = (config.id.clone(), config.name.clone());
+        self.status = match apply_config(id) {
+            Ok(()) => format!("Applied {}", name),
+            Err(e) => format!("Error: {}", e),
+        };
+        self.active = get_current_config();
+    }
+
+    fn detail(&self) -> Vec<Line<'static>> {
+        let Some(config) = self.selected() else {
+            return vec![Line::from("No profiles")];
+        };
+        let result = match self.pane {
+            Pane::Models => cli::masked_models(&config.id).map(|models| {
+                models.iter().flat_map(model_lines).collect::<Vec<_>>()
+            }),
+            Pane::Diff => apply_diff(&config.id).map(|diffs| {
+                cli::diff_text(&diffs).lines().map(diff_line).collect()
+            }),
+        };
+        result.unwrap_or_else(|e| vec![Line::styled(e, Style::new().fg(Color::Red))])
+    }
+}
+
+fn model_lines(model: &serde_json::Value) -> Vec<Line<'static>> {
+    let field = |key: &str| model.get(key).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let dim = Style::new().fg(Color::DarkGray);
+    vec![
+        Line::styled(field("displayName"), Style::new().add_modifier(Modifier::BOLD)),
+        Line::from(vec![
+            Span::raw(format!("  {}", field("model"))),
+            Span::styled(format!("  {}", field("provider")), dim),
+        ]),
+        Line::styled(format!("  {}", field("baseUrl")), dim),
+        Line::styled(format!("  {}", field("apiKey")), dim),
+        Line::default(),
+    ]
+}
+
+fn diff_line(line: &str) -> Line<'static> {
+    let color = match line.chars().next() {
+        Some('+') => Color::Green,
+        Some('-') => Color::Red,
+        Some('~') => Color::Yellow,
+        _ => Color::Reset,
+    };
+    Line::styled(line.to_string(), Style::new().fg(color))
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [main, footer] = Layout::vertical([Constraint::Min(1), Constraint::Length(1)])
+        .areas(frame.area());
+    let [left, right] = Layout::horizontal([Constraint::Percentage(35), Constraint::Min(1)])
+        .areas(main);
+
+    let items: Vec<ListItem> = app
+        .configs
+        .iter()
+        .map(|c| {
+            if Some(&c.id) == app.active.as_ref() {
+                let style = Style::new().fg(Color::Green).add_modifier(Modifier::BOLD);
+                ListItem::new(format!("● {}", c.name)).style(style)
+            } else {
+                ListItem::new(format!("  {}", c.name))
+            }
+        })
+        .collect();
+    let list = List::new(items)
+        .block(Block::bordered().title(" Profiles "))
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+    frame.render_stateful_widget(list, left, &mut app.list);
+
+    let title = match app.pane {
+        Pane::Models => " Models ",
+        Pane::Diff => " Apply diff ",
+    };
+    let detail = Paragraph::new(app.detail())
+        .block(Block::bordered().title(title))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(detail, right);
+
+    let status = if app.status.is_empty() { HELP } else { app.status.as_str() };
+    frame.render_widget(Paragraph::new(status), footer);
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), String> {
+    loop {
+        terminal.draw(|frame| draw(frame, app)).map_err(|e| e.to_string())?;
+        let Event::Key(key) = event::read().map_err(|e| e.to_string())? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        app.status.clear();
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Down | KeyCode::Char('j') => app.move_selection(true),
+            KeyCode::Up | KeyCode::Char('k') => app.move_selection(false),
+            KeyCode::Enter => app.apply_selected(),
+            KeyCode::Char('d') | KeyCode::Tab => {
+                app.pane = match app.pane {
+                    Pane::Models => Pane::Diff,
+                    Pane::Diff => Pane::Models,
+                };
+            }
+            KeyCode::Char('r') => {
+                app.reload();
+                app.status = "Reloaded".to_string();
+            }
+            _ => {}
+        }
+    }
+}
+
+pub fn run() -> Result<(), String> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut App::new());
+    ratatui::restore();
+    result
+}
@@ -0,0 +1,115 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{json_text, models};
+
+#[derive(Serialize)]
+pub struct ValidationError {
+    pub(crate) message: String,
+    // 1-based
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    // JSON pointer to the offending value, e.g. "/customModels/1/max_tokens"
+    pub(crate) pointer: String,
+}
+
+// Pointer of the innermost value around a byte offset
+fn pointer_at(text: &str, offset: usize) -> String {
+    match json_text::scan(text) {
+        Err(e) => e.pointer,
+        Ok(spans) => spans
+            .iter()
+            .filter(|(_, (start, end))| *start <= offset && offset <= *end)
+            .min_by_key(|(_, (start, end))| end - start)
+            .map(|(pointer, _)| pointer.clone())
+            .unwrap_or_default(),
+    }
+}
+
+fn error_at(
+    text: &str,
+    spans: &json_text::Spans,
+    pointer: String,
+    message: String,
+) -> ValidationError {
+    let offset = spans.get(&pointer).map(|(start, _)| *start).unwrap_or(0);
+    let (line, column) = json_text::line_col(text, offset);
+    ValidationError { message, line, column, pointer }
+}
+
+// Byte offset of a 1-based line and column as reported by serde_json, whose
+// columns count bytes rather than characters
+fn offset_of(text: &str, line: usize, column: usize) -> usize {
+    let line_start: usize = text
+        .split_inclusive('\n')
+        .take(line.saturating_sub(1))
+        .map(str::len)
+        .sum();
+    (line_start + column.saturating_sub(1)).min(text.len())
+}
+
+// Check that profile text parses and has the expected shape; no errors means it is valid
+pub(crate) fn validate_profile_text(text: &str) -> Vec<ValidationError> {
+    let config: Value = match serde_json::from_str(text) {
+        Ok(config) => config,
+        Err(e) => {
+            // Report a character column like the other errors; drop serde's own
+            // "at line .. column .." suffix, which counts bytes
+            let offset = offset_of(text, e.line(), e.column());
+            let (line, column) = json_text::line_col(text, offset);
+            let message = e.to_string();
+            let message = match message.rsplit_once(" at line ") {
+                Some((message, _)) => message.to_string(),
+                None => message,
+            };
+            return vec![ValidationError { message, line, column, pointer: pointer_at(text, offset) }];
+        }
+    };
+    let spans = json_text::scan(text).unwrap_or_default();
+    let mut errors = Vec::new();
+    let mut error = |pointer: String, message: &str| {
+        errors.push(error_at(text, &spans, pointer, message.to_string()));
+    };
+
+    let obj = match config.as_object() {
+        Some(obj) => obj,
+        None => {
+            error(String::new(), "Profile must be a JSON object");
+            return errors;
+        }
+    };
+    if obj.contains_key("customModels") && obj.contains_key("custom_models") {
+        error("/custom_models".to_string(), "Use either customModels or custom_models, not both");
+    }
+    for key in ["customModels", "custom_models"] {
+        match obj.get(key) {
+            None => {}
+            Some(Value::Array(list)) => {
+                for (i, model) in list.iter().enumerate() {
+                    if let Err(e) = models::check_model(&config, model) {
+                        let mut pointer = format!("/{}/{}", key, i);
+                        if let Some(field) = e.field {
+                            pointer = format!("{}/{}", pointer, json_text::pointer_part(field));
+                        }
+                        error(pointer, &e.message);
+                    }
+                }
+            }
+            Some(_) => error(format!("/{}", key), "Models must be an array"),
+        }
+    }
+    match obj.get("extends") {
+        None | Some(Value::String(_)) => {}
+        Some(Value::Array(ids)) if ids.iter().all(|v| v.is_string()) => {}
+        Some(_) => error("/extends".to_string(), "extends must be a profile ID or a list of them"),
+    }
+    match obj.get("removeModels") {
+        None => {}
+        Some(Value::Array(names)) if names.iter().all(|v| v.is_string()) => {}
+        Some(_) => error("/removeModels".to_string(), "removeModels must be a list of strings"),
+    }
+    if obj.get("_meta").map(|m| !m.is_object()).unwrap_or(false) {
+        error("/_meta".to_string(), "_meta must be an object");
+    }
+    errors
+}
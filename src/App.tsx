@@ -19,15 +19,74 @@ import {
 import { restrictToVerticalAxis, restrictToParentElement } from "@dnd-kit/modifiers";
 import "./App.css";
 
+interface ProfileMeta {
+  description: string | null;
+  color: string | null;
+  icon: string | null;
+  tags: string[];
+  createdAt: number | null;
+  lastAppliedAt: number | null;
+  applyCount: number;
+}
+
 interface ConfigFile {
+  id: string;
   name: string;
   path: string;
+  group: string | null;
+  meta: ProfileMeta;
+}
+
+interface Template {
+  id: string;
+  name: string;
+  description: string | null;
+  builtin: boolean;
+  content: unknown;
 }
 
 interface AppSettings {
   factory_path: string | null;
 }
 
+interface Diagnostic {
+  rule: string;
+  severity: "error" | "warning";
+  message: string;
+  pointer: string;
+}
+
+interface ValidationError {
+  message: string;
+  line: number;
+  column: number;
+  pointer: string;
+}
+
+interface SaveResult {
+  saved: boolean;
+  errors: ValidationError[];
+  diagnostics: Diagnostic[];
+  applied: boolean;
+}
+
+interface SchemaField {
+  name: string;
+  description?: string;
+}
+
+// Model fields from the profile JSON Schema, both spellings
+function schemaFields(schema: any): SchemaField[] {
+  const fields = new Map<string, SchemaField>();
+  for (const def of ["FactoryModel", "SimplifiedModel"]) {
+    const properties = schema?.definitions?.[def]?.properties ?? {};
+    for (const [name, prop] of Object.entries<any>(properties)) {
+      fields.set(name, { name, description: prop.description });
+    }
+  }
+  return [...fields.values()];
+}
+
 interface SortableItemProps {
   cfg: ConfigFile;
   currentConfig: string | null;
@@ -51,7 +110,7 @@ function SortableItem({ cfg, currentConfig, apply, selectConfig, duplicate, del
     setNodeRef,
     transform,
     transition,
-  } = useSortable({ id: cfg.name });
+  } = useSortable({ id: cfg.id });
 
   const style = {
     transform: transform ? `translateY(${transform.y}px)` : undefined,
@@ -62,24 +121,24 @@ function SortableItem({ cfg, currentConfig, apply, selectConfig, duplicate, del
     <div
       ref={setNodeRef}
       style={style}
-      className={`config-card ${currentConfig === cfg.path ? "current" : ""}`}
+      className={`config-card ${currentConfig === cfg.id ? "current" : ""}`}
     >
       <span className="drag-handle" {...attributes} {...listeners}>⋮⋮</span>
       <div className="config-icon"><FactoryIconSmall /></div>
       <div className="config-info">
         <div className="config-name">
-          {cfg.name}
-          {currentConfig === cfg.path && <span className="current-tag">当前使用</span>}
+          {cfg.meta.icon && `${cfg.meta.icon} `}{cfg.group && `${cfg.group} / `}{cfg.name}
+          {currentConfig === cfg.id && <span className="current-tag">当前使用</span>}
         </div>
-        <div className="config-url">{cfg.path}</div>
+        <div className="config-url">{cfg.meta.description || cfg.path}</div>
       </div>
       <div className="config-actions">
         <button
-          className={`action-btn apply-btn ${currentConfig === cfg.path ? "applied" : ""}`}
+          className={`action-btn apply-btn ${currentConfig === cfg.id ? "applied" : ""}`}
           onClick={() => apply(cfg)}
-          disabled={currentConfig === cfg.path}
+          disabled={currentConfig === cfg.id}
         >
-          {currentConfig === cfg.path ? "✓ 已启用" : "▶ 启用"}
+          {currentConfig === cfg.id ? "✓ 已启用" : "▶ 启用"}
         </button>
         <button className="action-icon" onClick={() => selectConfig(cfg)} title="编辑">✎</button>
         <button className="action-icon" onClick={() => duplicate(cfg)} title="复制">⧉</button>
@@ -93,12 +152,17 @@ function App() {
   const [configs, setConfigs] = useState<ConfigFile[]>([]);
   const [selected, setSelected] = useState<ConfigFile | null>(null);
   const [content, setContent] = useState("");
+  // The profile as it is on disk, which the model list edits
+  const [savedContent, setSavedContent] = useState("");
   const [showEditor, setShowEditor] = useState(false);
   const [showCreate, setShowCreate] = useState(false);
   const [showSettings, setShowSettings] = useState(false);
   const [renameName, setRenameName] = useState("");
   const [newName, setNewName] = useState("");
   const [newContent, setNewContent] = useState('{\n  "customModels": []\n}');
+  const [templates, setTemplates] = useState<Template[]>([]);
+  const [templateId, setTemplateId] = useState("");
+  const [fields, setFields] = useState<SchemaField[]>([]);
   const [status, setStatus] = useState("");
   const [currentConfig, setCurrentConfig] = useState<string | null>(null);
   const [factoryPath, setFactoryPath] = useState("");
@@ -117,6 +181,16 @@ function App() {
     setCurrentConfig(current);
   };
 
+  const loadTemplates = async () => {
+    setTemplates(await invoke<Template[]>("list_templates"));
+  };
+
+  const chooseTemplate = (id: string) => {
+    setTemplateId(id);
+    const template = templates.find(t => t.id === id);
+    setNewContent(template ? JSON.stringify(template.content, null, 2) : '{\n  "customModels": []\n}');
+  };
+
   const loadSettings = async () => {
     const settings = await invoke<AppSettings>("get_app_settings");
     const defPath = await invoke<string>("get_default_factory_path");
@@ -152,6 +226,7 @@ function App() {
     win.setFocus();
     loadSettings().then(() => loadConfigs()).finally(() => setLoading(false));
     checkDroid();
+    invoke("get_profile_schema").then(schema => setFields(schemaFields(schema)));
     
     const unlisten = listen<string>("config-changed", () => loadConfigs());
     return () => { unlisten.then(fn => fn()); };
@@ -163,22 +238,72 @@ function App() {
   };
 
   const selectConfig = async (cfg: ConfigFile) => {
-    const data = await invoke<string>("read_config", { path: cfg.path });
+    const data = await invoke<string>("read_config", { id: cfg.id });
     setSelected(cfg);
     setContent(data);
+    setSavedContent(data);
     setRenameName(cfg.name);
     setShowEditor(true);
   };
 
+  // Models of the saved profile, for the model list. The buttons edit the file
+  // on disk, so they are disabled while the textarea has unsaved changes.
+  const unsaved = content !== savedContent;
+  const editorModels = (): { name: string; enabled: boolean }[] => {
+    try {
+      const parsed = JSON.parse(savedContent);
+      const list = parsed.customModels ?? parsed.custom_models ?? [];
+      return list.map((m: Record<string, unknown>) => ({
+        name: String(m.displayName ?? m.model_display_name ?? m.ref ?? "?"),
+        enabled: m.enabled !== false,
+      }));
+    } catch {
+      return [];
+    }
+  };
+
+  const modelAction = async (command: string, args: Record<string, unknown>) => {
+    if (!selected) return;
+    try {
+      await invoke(command, { id: selected.id, ...args });
+      const data = await invoke<string>("read_config", { id: selected.id });
+      setContent(data);
+      setSavedContent(data);
+      loadConfigs();
+    } catch (e) {
+      showStatus(`操作失败: ${e}`);
+    }
+  };
+
+  const saveAsTemplate = async () => {
+    if (!selected) return;
+    try {
+      await invoke("save_template", { name: renameName.trim() || selected.name, content });
+      showStatus(`已存为模板: ${renameName.trim() || selected.name}`);
+    } catch (e) {
+      showStatus(`保存模板失败: ${e}`);
+    }
+  };
+
   const save = async () => {
     if (!selected) return;
-    let finalPath = selected.path;
-    
-    // Handle rename if name changed
+    let result = await invoke<SaveResult>("save_config", { id: selected.id, content });
+    if (!result.saved) {
+      const first = result.errors[0];
+      const where = `第 ${first.line} 行第 ${first.column} 列${first.pointer ? ` (${first.pointer})` : ""}`;
+      showStatus(`校验失败: ${first.message}，位于${where}`);
+      if (!confirm(`配置有 ${result.errors.length} 处错误:\n${first.message}\n位于${where}\n\n仍要保存吗？`)) {
+        return;
+      }
+      result = await invoke<SaveResult>("save_config", { id: selected.id, content, force: true });
+    }
+    const { diagnostics } = result;
+
+    // Handle rename if name changed (the ID stays the same, only the display name changes)
     if (renameName.trim() && renameName.trim() !== selected.name) {
       try {
-        finalPath = await invoke<string>("rename_config", {
-          oldPath: selected.path,
+        await invoke<string>("rename_config", {
+          id: selected.id,
           newName: renameName.trim()
         });
       } catch (e) {
@@ -187,38 +312,42 @@ function App() {
       }
     }
     
-    await invoke("save_config", { path: finalPath, content });
-    if (currentConfig === selected.path || currentConfig === finalPath) {
-      await invoke("apply_config", { path: finalPath });
-      setCurrentConfig(finalPath);
-    }
     loadConfigs();
-    showStatus(`已保存: ${renameName.trim() || selected.name}`);
+    if (diagnostics.length > 0) {
+      showStatus(`已保存，发现 ${diagnostics.length} 个问题: ${diagnostics[0].message} (${diagnostics[0].pointer})`);
+    } else {
+      showStatus(`已保存: ${renameName.trim() || selected.name}`);
+    }
     setShowEditor(false);
   };
 
   const apply = async (cfg: ConfigFile) => {
-    await invoke("apply_config", { path: cfg.path });
-    setCurrentConfig(cfg.path);
+    await invoke("apply_config", { id: cfg.id });
+    setCurrentConfig(cfg.id);
     showStatus(`已启用: ${cfg.name}`);
   };
 
   const create = async () => {
     if (!newName.trim()) return;
-    const path = await invoke<string>("create_config", { name: newName.trim() });
+    const id = await invoke<string>("create_config", {
+      name: newName.trim(),
+      template: templateId || null,
+    });
     if (newContent.trim()) {
-      await invoke("save_config", { path, content: newContent });
+      // A freshly created profile is never active, so invalid content is kept as typed
+      await invoke("save_config", { id, content: newContent, force: true });
     }
     setNewName("");
     setNewContent('{\n  "customModels": []\n}');
+    setTemplateId("");
     setShowCreate(false);
     loadConfigs();
     showStatus(`已创建: ${newName}`);
   };
 
   const del = async (cfg: ConfigFile) => {
-    await invoke("delete_config", { path: cfg.path });
-    if (selected?.path === cfg.path) {
+    await invoke("delete_config", { id: cfg.id });
+    if (selected?.id === cfg.id) {
       setSelected(null);
       setShowEditor(false);
     }
@@ -233,12 +362,9 @@ function App() {
   };
 
   const duplicate = async (cfg: ConfigFile) => {
-    const content = await invoke<string>("read_config", { path: cfg.path });
-    const newName = `${cfg.name}-copy`;
-    const newPath = await invoke<string>("create_config", { name: newName });
-    await invoke("save_config", { path: newPath, content });
+    await invoke<string>("duplicate_config", { id: cfg.id });
     loadConfigs();
-    showStatus(`已复制: ${newName}`);
+    showStatus(`已复制: ${cfg.name}`);
   };
 
   const saveSettings = async () => {
@@ -260,11 +386,11 @@ function App() {
   const handleDragEnd = async (event: DragEndEvent) => {
     const { active, over } = event;
     if (over && active.id !== over.id) {
-      const oldIndex = configs.findIndex(c => c.name === active.id);
-      const newIndex = configs.findIndex(c => c.name === over.id);
+      const oldIndex = configs.findIndex(c => c.id === active.id);
+      const newIndex = configs.findIndex(c => c.id === over.id);
       const newConfigs = arrayMove(configs, oldIndex, newIndex);
       setConfigs(newConfigs);
-      await invoke("save_config_order", { order: newConfigs.map(c => c.name) });
+      await invoke("save_config_order", { order: newConfigs.map(c => c.id) });
     }
   };
 
@@ -287,7 +413,7 @@ function App() {
 
         <div className="header-right">
           <button className="icon-btn" onClick={importCurrent} title="导入当前配置">↓</button>
-          <button className="add-btn" onClick={() => setShowCreate(true)}>+</button>
+          <button className="add-btn" onClick={() => { loadTemplates(); setShowCreate(true); }}>+</button>
         </div>
       </header>
 
@@ -299,11 +425,11 @@ function App() {
           </div>
         ) : (
           <DndContext sensors={sensors} collisionDetection={closestCenter} onDragEnd={handleDragEnd} modifiers={[restrictToVerticalAxis, restrictToParentElement]}>
-            <SortableContext items={configs.map(c => c.name)} strategy={verticalListSortingStrategy}>
+            <SortableContext items={configs.map(c => c.id)} strategy={verticalListSortingStrategy}>
               <div className="config-list">
                 {configs.map(cfg => (
                   <SortableItem
-                    key={cfg.name}
+                    key={cfg.id}
                     cfg={cfg}
                     currentConfig={currentConfig}
                     apply={apply}
@@ -335,10 +461,53 @@ function App() {
                   placeholder="配置名称"
                 />
               </div>
+              {editorModels().length > 0 && (
+                <>
+                  <label>模型{unsaved && " (请先保存配置内容)"}</label>
+                  <div className="model-list">
+                    {editorModels().map((m, i, all) => (
+                      <div key={i} className={`model-row ${m.enabled ? "" : "disabled"}`}>
+                        <span className="model-row-name">{m.name}</span>
+                        <button
+                          className="action-icon"
+                          disabled={unsaved || i === 0}
+                          onClick={() => modelAction("reorder_model", { from: i, to: i - 1 })}
+                          title="上移"
+                        >↑</button>
+                        <button
+                          className="action-icon"
+                          disabled={unsaved || i === all.length - 1}
+                          onClick={() => modelAction("reorder_model", { from: i, to: i + 1 })}
+                          title="下移"
+                        >↓</button>
+                        <button
+                          className="action-icon"
+                          disabled={unsaved}
+                          onClick={() => modelAction("set_model_enabled", { index: i, enabled: !m.enabled })}
+                          title={m.enabled ? "禁用" : "启用"}
+                        >{m.enabled ? "⏸" : "▶"}</button>
+                        <button
+                          className="action-icon danger"
+                          disabled={unsaved}
+                          onClick={() => modelAction("remove_model", { index: i })}
+                          title="删除"
+                        >🗑</button>
+                      </div>
+                    ))}
+                  </div>
+                </>
+              )}
               <label>配置内容</label>
               <textarea value={content} onChange={e => setContent(e.target.value)} spellCheck={false} />
+              <details className="schema-fields">
+                <summary>可用模型字段</summary>
+                {fields.map(f => (
+                  <div key={f.name}><code>{f.name}</code>{f.description && ` — ${f.description}`}</div>
+                ))}
+              </details>
             </div>
             <div className="modal-footer">
+              <button className="btn btn-default" onClick={saveAsTemplate}>存为模板</button>
               <button className="btn btn-default" onClick={() => setShowEditor(false)}>取消</button>
               <button className="btn btn-primary" onClick={save}>保存</button>
             </div>
@@ -364,6 +533,21 @@ function App() {
                   autoFocus
                 />
               </div>
+              <div className="editor-name-row">
+                <label>模板</label>
+                <select
+                  className="editor-name-input"
+                  value={templateId}
+                  onChange={e => chooseTemplate(e.target.value)}
+                >
+                  <option value="">空白配置</option>
+                  {templates.map(t => (
+                    <option key={t.id} value={t.id}>
+                      {t.builtin ? t.name : `${t.name} (自定义)`}
+                    </option>
+                  ))}
+                </select>
+              </div>
               <label>配置内容</label>
               <textarea
                 value={newContent}